@@ -0,0 +1,103 @@
+use std::f64::consts::PI;
+use std::path::Path;
+use std::sync::Arc;
+
+use anyhow::{
+    anyhow,
+    Result,
+};
+
+use glam::{
+    DVec2,
+    DVec3,
+};
+
+use image::ImageReader;
+
+use rand::{
+    Rng,
+    RngCore,
+};
+
+use crate::distribution::Distribution2D;
+use crate::vector::*;
+
+#[derive(Clone, Debug)]
+pub struct ApertureMask {
+    // Texels are sampled proportionally to their value.
+    distribution: Distribution2D,
+}
+
+impl ApertureMask {
+    pub fn try_from_path<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let image = ImageReader::open(path.as_ref())?.decode()?.to_luma32f();
+
+        if image.pixels().all(|p| p.0[0] <= 0.0) {
+            return Err(anyhow!("the aperture mask is entirely black"));
+        }
+
+        let distribution = Distribution2D::from_fn(image.width(), image.height(), |x, y| {
+            image.get_pixel(x, y).0[0] as f64
+        });
+
+        Ok(Self { distribution })
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub enum Aperture {
+    #[default]
+    Circle,
+    Polygon {
+        blades: usize,
+        rotation: f64,
+    },
+    Mask(Arc<ApertureMask>),
+}
+
+impl Aperture {
+    fn sample_polygon(
+        blades: usize,
+        rotation: f64,
+        rng: &mut dyn RngCore,
+    ) -> DVec3 {
+        // Pick one of the triangles made of the center of the polygon and
+        // two consecutive vertices then sample it uniformly.
+        let blade = rng.random_range(0..blades) as f64;
+        let angle = 2.0*PI/(blades as f64);
+
+        let a = DVec2::from_angle(rotation + blade*angle);
+        let b = DVec2::from_angle(rotation + (blade + 1.0)*angle);
+
+        let r1 = rng.random::<f64>().sqrt();
+        let r2 = rng.random::<f64>();
+
+        (r1*(1.0 - r2)*a + r1*r2*b).extend(0.0)
+    }
+
+    fn sample_mask(
+        mask: &ApertureMask,
+        rng: &mut dyn RngCore,
+    ) -> DVec3 {
+        // Texture coordinates are mapped to the square bounding the unit
+        // disk.
+        (2.0*mask.distribution.sample(rng) - DVec2::ONE).extend(0.0)
+    }
+
+    pub fn sample(
+        &self,
+        rng: &mut dyn RngCore,
+    ) -> DVec3 {
+        match self {
+            Self::Circle => {
+                random_in_unit_disk(rng)
+            },
+            Self::Polygon { blades, rotation } => {
+                Self::sample_polygon(*blades, *rotation, rng)
+            },
+            Self::Mask(mask) => {
+                Self::sample_mask(mask, rng)
+            },
+        }
+    }
+}
@@ -0,0 +1,41 @@
+use std::f64::consts::PI;
+use std::fmt::Debug;
+
+use glam::{
+    DVec2,
+    DVec3,
+};
+
+use rand::RngCore;
+
+pub trait Background: Debug {
+    fn get_color(&self, direction: DVec3) -> DVec3;
+
+    // Returns a direction toward the background together with its
+    // probability density, backgrounds that cannot be importance sampled
+    // return None.
+    fn sample(
+        &self,
+        _rng: &mut dyn RngCore,
+    ) -> Option<(DVec3, f64)> {
+        None
+    }
+
+    fn pdf(
+        &self,
+        _direction: DVec3,
+    ) -> f64 {
+        0.0
+    }
+}
+
+// Maps a direction to equirectangular texture coordinates, same mapping as
+// the sphere texture coordinates.
+pub(crate) fn direction_to_uv(direction: DVec3) -> DVec2 {
+    let d = direction.normalize();
+
+    let theta = f64::acos((-d.y).clamp(-1.0, 1.0));
+    let phi = f64::atan2(-d.z, d.x) + PI;
+
+    DVec2::new(phi/(2.0*PI), theta/PI)
+}
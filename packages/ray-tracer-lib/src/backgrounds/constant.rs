@@ -0,0 +1,26 @@
+use glam::DVec3;
+
+use super::background::Background;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ConstantBackground {
+    color: DVec3,
+}
+
+impl ConstantBackground {
+    pub fn new(color: DVec3) -> Self {
+        Self { color }
+    }
+}
+
+impl Default for ConstantBackground {
+    fn default() -> Self {
+        Self::new(DVec3::ZERO)
+    }
+}
+
+impl Background for ConstantBackground {
+    fn get_color(&self, _: DVec3) -> DVec3 {
+        self.color
+    }
+}
@@ -0,0 +1,151 @@
+use std::f64::consts::PI;
+
+use glam::{
+    DMat3,
+    DVec2,
+    DVec3,
+};
+
+use rand::RngCore;
+
+use crate::distribution::Distribution2D;
+use crate::spectrum::luminance;
+use crate::textures::{
+    Image,
+    Texture,
+};
+
+use super::background::{
+    Background,
+    direction_to_uv,
+};
+
+fn uv_to_direction(uv: DVec2) -> DVec3 {
+    let theta = uv.y*PI;
+    let phi = uv.x*2.0*PI - PI;
+
+    DVec3::new(
+        theta.sin()*phi.cos(),
+        -theta.cos(),
+        -theta.sin()*phi.sin(),
+    )
+}
+
+#[derive(Clone, Debug)]
+pub struct EnvironmentMap {
+    texture: Image,
+    intensity: f64,
+    rotation_mat: DMat3,
+    rotation_mat_inv: DMat3,
+    distribution: Option<Distribution2D>,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct EnvironmentMapBuilder {
+    intensity: Option<f64>,
+    rotation: Option<f64>,
+    importance_sampling: Option<bool>,
+}
+
+impl EnvironmentMapBuilder {
+    pub fn with_intensity(
+        &mut self,
+        value: Option<f64>,
+    ) -> &mut Self {
+        self.intensity = value;
+        self
+    }
+
+    pub fn with_rotation(
+        &mut self,
+        value: Option<f64>,
+    ) -> &mut Self {
+        self.rotation = value;
+        self
+    }
+
+    pub fn with_importance_sampling(
+        &mut self,
+        value: Option<bool>,
+    ) -> &mut Self {
+        self.importance_sampling = value;
+        self
+    }
+
+    pub fn build(self, texture: Image) -> EnvironmentMap {
+        let intensity = self.intensity.unwrap_or(1.0);
+        let rotation = self.rotation.unwrap_or(0.0);
+
+        let rotation_mat = DMat3::from_rotation_y(rotation);
+        let rotation_mat_inv = rotation_mat.transpose();
+
+        let distribution =
+            if self.importance_sampling.unwrap_or(true) {
+                // Pixels are weighted by their luminance and by the solid
+                // angle they cover on the sphere.
+                let height = texture.height();
+
+                Some(Distribution2D::from_fn(texture.width(), height, |x, y| {
+                    let theta = PI*((y as f64) + 0.5)/(height as f64);
+
+                    theta.sin()*luminance(texture.get_pixel(x, y))
+                }))
+            } else {
+                None
+            };
+
+        EnvironmentMap {
+            texture,
+            intensity,
+            rotation_mat,
+            rotation_mat_inv,
+            distribution,
+        }
+    }
+}
+
+impl Background for EnvironmentMap {
+    fn get_color(&self, direction: DVec3) -> DVec3 {
+        let direction = self.rotation_mat_inv*direction;
+        let uv = direction_to_uv(direction);
+
+        self.intensity*self.texture.get_color(uv, direction)
+    }
+
+    fn sample(
+        &self,
+        rng: &mut dyn RngCore,
+    ) -> Option<(DVec3, f64)> {
+        let distribution = self.distribution.as_ref()?;
+
+        let uv = distribution.sample(rng);
+        let sin_theta = (uv.y*PI).sin();
+
+        if sin_theta <= 0.0 {
+            return None;
+        }
+
+        let direction = self.rotation_mat*uv_to_direction(uv);
+        let pdf = distribution.pdf(uv)/(2.0*PI*PI*sin_theta);
+
+        Some((direction, pdf))
+    }
+
+    fn pdf(
+        &self,
+        direction: DVec3,
+    ) -> f64 {
+        if let Some(distribution) = self.distribution.as_ref() {
+            let uv = direction_to_uv(self.rotation_mat_inv*direction);
+            let sin_theta = (uv.y*PI).sin();
+
+            if sin_theta > 0.0 {
+                distribution.pdf(uv)/(2.0*PI*PI*sin_theta)
+            } else {
+                0.0
+            }
+        } else {
+            0.0
+        }
+    }
+}
@@ -0,0 +1,32 @@
+use glam::DVec3;
+
+use super::background::Background;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct GradientBackground {
+    horizon: DVec3,
+    zenith: DVec3,
+}
+
+impl GradientBackground {
+    pub fn new(horizon: DVec3, zenith: DVec3) -> Self {
+        Self { horizon, zenith }
+    }
+}
+
+impl Default for GradientBackground {
+    fn default() -> Self {
+        Self::new(DVec3::ONE, DVec3::new(0.5, 0.7, 1.0))
+    }
+}
+
+impl Background for GradientBackground {
+    fn get_color(&self, direction: DVec3) -> DVec3 {
+        // Blend from the horizon color looking straight ahead to the zenith
+        // color looking straight up, directions below the horizon keep the
+        // horizon color.
+        let a = direction.normalize().y.max(0.0);
+
+        self.horizon.lerp(self.zenith, a)
+    }
+}
@@ -0,0 +1,13 @@
+mod background;
+mod constant;
+mod environment_map;
+mod gradient;
+mod sky;
+mod texture;
+
+pub use background::*;
+pub use constant::*;
+pub use environment_map::*;
+pub use gradient::*;
+pub use sky::*;
+pub use texture::*;
@@ -0,0 +1,288 @@
+use std::f64::consts::{
+    FRAC_PI_2,
+    PI,
+};
+
+use glam::DVec3;
+
+use rand::{
+    Rng,
+    RngCore,
+};
+
+use crate::spectrum::xyz_to_rgb;
+
+use super::background::Background;
+
+// Preetham, Shirley and Smits, "A Practical Analytic Model for Daylight".
+
+const SUN_ANGULAR_RADIUS: f64 = 0.00465;
+
+// Scale from the model luminance (kcd/m²) to the renderer radiance.
+const SKY_LUMINANCE_SCALE: f64 = 0.05;
+
+#[derive(Clone, Copy, Debug)]
+struct Perez {
+    a: f64,
+    b: f64,
+    c: f64,
+    d: f64,
+    e: f64,
+}
+
+impl Perez {
+    fn eval(&self, cos_theta: f64, gamma: f64) -> f64 {
+        (1.0 + self.a*f64::exp(self.b/cos_theta))
+            *(1.0 + self.c*f64::exp(self.d*gamma) + self.e*gamma.cos().powi(2))
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+struct PerezChannel {
+    perez: Perez,
+    // Value of the channel at the zenith divided by the Perez function at
+    // the zenith.
+    scale: f64,
+}
+
+impl PerezChannel {
+    fn new(perez: Perez, zenith: f64, sun_theta: f64) -> Self {
+        Self {
+            perez,
+            scale: zenith/perez.eval(1.0, sun_theta),
+        }
+    }
+
+    fn eval(&self, cos_theta: f64, gamma: f64) -> f64 {
+        self.scale*self.perez.eval(cos_theta, gamma)
+    }
+}
+
+fn yxy_to_rgb(luminance: f64, x: f64, y: f64) -> DVec3 {
+    if y <= 0.0 {
+        return DVec3::ZERO;
+    }
+
+    let xyz = DVec3::new(
+        x*luminance/y,
+        luminance,
+        (1.0 - x - y)*luminance/y,
+    );
+
+    xyz_to_rgb(xyz).max(DVec3::ZERO)
+}
+
+#[derive(Clone, Debug)]
+pub struct PhysicalSky {
+    sun_direction: DVec3,
+    sun_cos_max: f64,
+    sun_radiance: DVec3,
+    intensity: f64,
+    luminance: PerezChannel,
+    chromaticity_x: PerezChannel,
+    chromaticity_y: PerezChannel,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct PhysicalSkyBuilder {
+    sun_direction: Option<DVec3>,
+    turbidity: Option<f64>,
+    intensity: Option<f64>,
+    sun_intensity: Option<f64>,
+    sun_size: Option<f64>,
+}
+
+impl PhysicalSkyBuilder {
+    pub fn with_sun_direction(
+        &mut self,
+        value: Option<DVec3>,
+    ) -> &mut Self {
+        self.sun_direction = value;
+        self
+    }
+
+    pub fn with_turbidity(
+        &mut self,
+        value: Option<f64>,
+    ) -> &mut Self {
+        self.turbidity = value;
+        self
+    }
+
+    pub fn with_intensity(
+        &mut self,
+        value: Option<f64>,
+    ) -> &mut Self {
+        self.intensity = value;
+        self
+    }
+
+    pub fn with_sun_intensity(
+        &mut self,
+        value: Option<f64>,
+    ) -> &mut Self {
+        self.sun_intensity = value;
+        self
+    }
+
+    // Multiplier applied to the apparent size of the sun disk.
+    pub fn with_sun_size(
+        &mut self,
+        value: Option<f64>,
+    ) -> &mut Self {
+        self.sun_size = value;
+        self
+    }
+
+    pub fn build(self) -> PhysicalSky {
+        let sun_direction = self.sun_direction
+            .unwrap_or(DVec3::new(0.0, 1.0, 1.0))
+            .normalize();
+        let turbidity = self.turbidity.unwrap_or(2.5).clamp(1.0, 20.0);
+        let intensity = self.intensity.unwrap_or(1.0);
+        let sun_intensity = self.sun_intensity.unwrap_or(4.0);
+        let sun_size = self.sun_size.unwrap_or(1.0).max(0.0);
+
+        // The model is not defined for a sun below the horizon.
+        let sun_theta = sun_direction.y.clamp(0.0, 1.0).acos().min(FRAC_PI_2 - 0.001);
+
+        let t = turbidity;
+        let t2 = t*t;
+
+        let chi = (4.0/9.0 - t/120.0)*(PI - 2.0*sun_theta);
+        let zenith_luminance = (4.0453*t - 4.9710)*chi.tan() - 0.2155*t + 2.4192;
+
+        let th = sun_theta;
+        let th2 = th*th;
+        let th3 = th2*th;
+
+        let zenith_x =
+            t2*(0.00166*th3 - 0.00375*th2 + 0.00209*th)
+            + t*(-0.02903*th3 + 0.06377*th2 - 0.03202*th + 0.00394)
+            + (0.11693*th3 - 0.21196*th2 + 0.06052*th + 0.25886);
+        let zenith_y =
+            t2*(0.00275*th3 - 0.00610*th2 + 0.00317*th)
+            + t*(-0.04214*th3 + 0.08970*th2 - 0.04153*th + 0.00516)
+            + (0.15346*th3 - 0.26756*th2 + 0.06670*th + 0.26688);
+
+        let luminance = PerezChannel::new(Perez {
+            a:  0.1787*t - 1.4630,
+            b: -0.3554*t + 0.4275,
+            c: -0.0227*t + 5.3251,
+            d:  0.1206*t - 2.5771,
+            e: -0.0670*t + 0.3703,
+        }, zenith_luminance, sun_theta);
+
+        let chromaticity_x = PerezChannel::new(Perez {
+            a: -0.0193*t - 0.2592,
+            b: -0.0665*t + 0.0008,
+            c: -0.0004*t + 0.2125,
+            d: -0.0641*t - 0.8989,
+            e: -0.0033*t + 0.0452,
+        }, zenith_x, sun_theta);
+
+        let chromaticity_y = PerezChannel::new(Perez {
+            a: -0.0167*t - 0.2608,
+            b: -0.0950*t + 0.0092,
+            c: -0.0079*t + 0.2102,
+            d: -0.0441*t - 1.6537,
+            e: -0.0109*t + 0.0529,
+        }, zenith_y, sun_theta);
+
+        // The sun disk takes the hue of the sky around it, which gets redder
+        // as the sun comes closer to the horizon.
+        let sun_cos_theta = f64::cos(sun_theta);
+        let sun_color = yxy_to_rgb(
+            1.0,
+            chromaticity_x.eval(sun_cos_theta, 0.0),
+            chromaticity_y.eval(sun_cos_theta, 0.0),
+        );
+        let sun_color = sun_color/sun_color.max_element().max(f64::EPSILON);
+
+        // Sun radiance is normalized so that the irradiance it delivers does
+        // not depend on the size of the disk.
+        let sun_angular_radius = (sun_size*SUN_ANGULAR_RADIUS).min(FRAC_PI_2);
+        let sun_cos_max = sun_angular_radius.cos();
+        let sun_solid_angle = 2.0*PI*(1.0 - sun_cos_max);
+        let sun_radiance =
+            if sun_solid_angle > 0.0 && sun_direction.y > 0.0 {
+                sun_intensity*sun_color/sun_solid_angle
+            } else {
+                DVec3::ZERO
+            };
+
+        PhysicalSky {
+            sun_direction,
+            sun_cos_max,
+            sun_radiance,
+            intensity,
+            luminance,
+            chromaticity_x,
+            chromaticity_y,
+        }
+    }
+}
+
+impl PhysicalSky {
+    fn has_sun(&self) -> bool {
+        self.sun_radiance != DVec3::ZERO
+    }
+
+    fn get_sky_color(&self, direction: DVec3) -> DVec3 {
+        // Below the horizon the sky keeps the horizon color.
+        let cos_theta = direction.y.max(0.001);
+        let gamma = direction.dot(self.sun_direction).clamp(-1.0, 1.0).acos();
+
+        yxy_to_rgb(
+            self.luminance.eval(cos_theta, gamma)*SKY_LUMINANCE_SCALE,
+            self.chromaticity_x.eval(cos_theta, gamma),
+            self.chromaticity_y.eval(cos_theta, gamma),
+        )
+    }
+}
+
+impl Background for PhysicalSky {
+    fn get_color(&self, direction: DVec3) -> DVec3 {
+        let direction = direction.normalize();
+        let sky = self.intensity*self.get_sky_color(direction);
+
+        if self.has_sun() && direction.dot(self.sun_direction) >= self.sun_cos_max {
+            sky + self.sun_radiance
+        } else {
+            sky
+        }
+    }
+
+    fn sample(
+        &self,
+        rng: &mut dyn RngCore,
+    ) -> Option<(DVec3, f64)> {
+        if !self.has_sun() {
+            return None;
+        }
+
+        // Uniform sampling of the cone subtended by the sun disk.
+        let cos_theta = 1.0 - rng.random::<f64>()*(1.0 - self.sun_cos_max);
+        let sin_theta = (1.0 - cos_theta*cos_theta).max(0.0).sqrt();
+        let phi = 2.0*PI*rng.random::<f64>();
+
+        let (u, v) = self.sun_direction.any_orthonormal_pair();
+        let direction =
+            sin_theta*phi.cos()*u
+            + sin_theta*phi.sin()*v
+            + cos_theta*self.sun_direction;
+
+        Some((direction, self.pdf(direction)))
+    }
+
+    fn pdf(
+        &self,
+        direction: DVec3,
+    ) -> f64 {
+        if self.has_sun() && direction.normalize().dot(self.sun_direction) >= self.sun_cos_max {
+            1.0/(2.0*PI*(1.0 - self.sun_cos_max))
+        } else {
+            0.0
+        }
+    }
+}
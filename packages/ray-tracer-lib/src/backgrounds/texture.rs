@@ -0,0 +1,29 @@
+use std::sync::Arc;
+
+use glam::DVec3;
+
+use crate::textures::Texture;
+
+use super::background::{
+    Background,
+    direction_to_uv,
+};
+
+#[derive(Clone, Debug)]
+pub struct TextureBackground {
+    texture: Arc<dyn Texture + Send + Sync>,
+}
+
+impl TextureBackground {
+    pub fn new(texture: Arc<dyn Texture + Send + Sync>) -> Self {
+        Self { texture }
+    }
+}
+
+impl Background for TextureBackground {
+    fn get_color(&self, direction: DVec3) -> DVec3 {
+        let direction = direction.normalize();
+
+        self.texture.get_color(direction_to_uv(direction), direction)
+    }
+}
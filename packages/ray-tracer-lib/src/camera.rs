@@ -1,6 +1,10 @@
 use std::f64::consts::PI;
-use std::f64::INFINITY;
-use std::usize;
+use std::sync::Arc;
+
+use anyhow::{
+    anyhow,
+    Result,
+};
 
 use glam::{
     DVec2,
@@ -19,23 +23,71 @@ use rayon::iter::{
     ParallelIterator,
 };
 
-use crate::hitable::Hitable;
+use crate::aperture::Aperture;
+use crate::backgrounds::{
+    Background,
+    ConstantBackground,
+};
+use crate::hitable::{
+    HitRecord,
+    Hitable,
+};
 use crate::image::ImageSize;
 use crate::interval::Interval;
+use crate::lights::Light;
 use crate::ray::Ray;
+use crate::spectrum::{
+    spectral_weight,
+    WAVELENGTH_MAX,
+    WAVELENGTH_MIN,
+};
 use crate::vector::*;
 
 
-#[derive(Clone, Copy, Debug)]
+fn power_heuristic(pdf: f64, other_pdf: f64) -> f64 {
+    let a = pdf*pdf;
+    let b = other_pdf*other_pdf;
+
+    if a + b > 0.0 {
+        a/(a + b)
+    } else {
+        0.0
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Projection {
+    #[default]
+    Perspective,
+    Orthographic {
+        view_height: f64,
+    },
+    Equirectangular,
+    // Equidistant fisheye, the field of view is the angle covered by the
+    // image circle and is independent of the camera field of view.
+    Fisheye {
+        field_of_view: f64,
+    },
+}
+
+#[derive(Clone, Debug)]
 pub struct CameraBuilder {
     image_size: ImageSize,
-    background_color: DVec3,
+    background: Arc<dyn Background + Send + Sync>,
     look_from: DVec3,
     look_at: DVec3,
     view_up: DVec3,
     defocus_angle: f64,
     focus_dist: f64,
+    focus_point: Option<DVec3>,
     field_of_view: f64,
+    focal_length: Option<f64>,
+    sensor_width: f64,
+    f_number: Option<f64>,
+    aperture: Aperture,
+    projection: Projection,
+    shutter: Interval,
+    spectral: bool,
     ray_max_bounces: usize,
     samples_per_pixel: usize,
 }
@@ -47,7 +99,12 @@ impl CameraBuilder {
     }
 
     pub fn with_background_color(&mut self, value: DVec3) -> &mut Self {
-        self.background_color = value;
+        self.background = Arc::new(ConstantBackground::new(value));
+        self
+    }
+
+    pub fn with_background(&mut self, value: Arc<dyn Background + Send + Sync>) -> &mut Self {
+        self.background = value;
         self
     }
 
@@ -73,6 +130,12 @@ impl CameraBuilder {
 
     pub fn with_focus_dist(&mut self, value: f64) -> &mut Self {
         self.focus_dist = value;
+        self.focus_point = None;
+        self
+    }
+
+    pub fn with_focus_point(&mut self, value: DVec3) -> &mut Self {
+        self.focus_point.replace(value);
         self
     }
 
@@ -81,6 +144,43 @@ impl CameraBuilder {
         self
     }
 
+    pub fn with_focal_length(&mut self, value: f64) -> &mut Self {
+        self.focal_length.replace(value);
+        self
+    }
+
+    pub fn with_sensor_width(&mut self, value: f64) -> &mut Self {
+        self.sensor_width = value;
+        self
+    }
+
+    pub fn with_f_number(&mut self, value: f64) -> &mut Self {
+        self.f_number.replace(value);
+        self
+    }
+
+    pub fn with_aperture(&mut self, value: Aperture) -> &mut Self {
+        self.aperture = value;
+        self
+    }
+
+    pub fn with_projection(&mut self, value: Projection) -> &mut Self {
+        self.projection = value;
+        self
+    }
+
+    pub fn with_shutter(&mut self, value: Interval) -> &mut Self {
+        self.shutter = value;
+        self
+    }
+
+    // Traces a single wavelength per sample, enabling the dispersion of
+    // dielectrics.
+    pub fn with_spectral(&mut self, value: bool) -> &mut Self {
+        self.spectral = value;
+        self
+    }
+
     pub fn with_ray_max_bounces(&mut self, value: usize) -> &mut Self {
         self.ray_max_bounces = value;
         self
@@ -91,10 +191,32 @@ impl CameraBuilder {
         self
     }
 
-    pub fn build(self) -> Camera {
+    pub fn try_build(self) -> Result<Camera> {
         let image_size = self.image_size;
+        let aperture = self.aperture;
 
-        let background_color = self.background_color;
+        if let Aperture::Polygon { blades, .. } = aperture && blades < 3 {
+            return Err(anyhow!(
+                "invalid aperture with {blades} blades, a polygon needs at least 3",
+            ));
+        }
+
+        // Motions are defined over a unit of time, the shutter interval must
+        // be within it.
+        let shutter = self.shutter;
+
+        if shutter.is_empty()
+            || !Self::DEFAULT_SHUTTER.contains(shutter.min)
+            || !Self::DEFAULT_SHUTTER.contains(shutter.max) {
+            return Err(anyhow!(
+                "invalid shutter interval [{}, {}], it must open before it closes within [0, 1]",
+                shutter.min,
+                shutter.max,
+            ));
+        }
+        let spectral = self.spectral;
+
+        let background = self.background;
 
         let look_at = self.look_at;
         let look_from = self.look_from;
@@ -103,48 +225,110 @@ impl CameraBuilder {
         let ray_max_bounces = self.ray_max_bounces;
         let samples_per_pixel = self.samples_per_pixel.max(1);
 
+        let w = (look_from - look_at).normalize();
+        let u = view_up.cross(w).normalize();
+        let v = w.cross(u).normalize();
+
         let defocus_angle = self.defocus_angle.clamp(0., PI);
-        let focus_dist = self.focus_dist;
+        let focus_dist = if let Some(focus_point) = self.focus_point {
+            (focus_point - look_from).dot(-w)
+        } else {
+            self.focus_dist
+        };
 
-        let field_of_view = self.field_of_view;
-        let h = (field_of_view/2.).tan();
+        if focus_dist <= 0.0 {
+            return Err(if self.focus_point.is_some() {
+                anyhow!("the focus point must be in front of the camera")
+            } else {
+                anyhow!("the focus distance must be positive: {focus_dist}")
+            });
+        }
 
-        let viewport_height = focus_dist*h*2.0;
-        let viewport_width = viewport_height*image_size.get_aspect_ratio();
+        let projection = self.projection;
 
-        let w = (look_from - look_at).normalize();
-        let u = view_up.cross(w).normalize();
-        let v = w.cross(u).normalize();
+        if let Projection::Orthographic { view_height } = projection && view_height <= 0.0 {
+            return Err(anyhow!("the view height must be positive: {view_height}"));
+        }
 
-        let viewport_u = u*viewport_width;
-        let viewport_v = -v*viewport_height;
+        // Vertical field of view of a sensor of the given width with the
+        // same aspect ratio as the image, and the matching focal length when
+        // only the field of view is given.
+        let sensor_height = self.sensor_width/image_size.get_aspect_ratio();
+        let (field_of_view, focal_length) = if let Some(focal_length) = self.focal_length {
+            (2.0*(sensor_height/(2.0*focal_length)).atan(), focal_length)
+        } else {
+            (self.field_of_view, sensor_height/(2.0*(self.field_of_view/2.0).tan()))
+        };
 
-        let viewport_pixel_delta_u = viewport_u/(image_size.width as f64);
-        let viewport_pixel_delta_v = viewport_v/(image_size.height as f64);
+        // Panoramic projections do not use the viewport, directions are
+        // computed from the pixel position in the image.
+        let viewport_height = match projection {
+            Projection::Perspective => {
+                let h = (field_of_view/2.).tan();
 
-        let viewport_top_left =
-                look_from
-                    - w*focus_dist
-                    - viewport_u/2.0
-                    - viewport_v/2.0
-                    + (viewport_pixel_delta_u + viewport_pixel_delta_v)/2.0
-                ;
+                Some(focus_dist*h*2.0)
+            },
+            Projection::Orthographic { view_height } => {
+                Some(view_height)
+            },
+            Projection::Equirectangular | Projection::Fisheye { .. } => {
+                None
+            },
+        };
 
-        let defocus_radius = focus_dist*(defocus_angle/2.0).tan();
+        let (
+            viewport_pixel_delta_u,
+            viewport_pixel_delta_v,
+            viewport_top_left,
+        ) = viewport_height.map(|viewport_height| {
+            let viewport_width = viewport_height*image_size.get_aspect_ratio();
+
+            let viewport_u = u*viewport_width;
+            let viewport_v = -v*viewport_height;
+
+            let viewport_pixel_delta_u = viewport_u/(image_size.width as f64);
+            let viewport_pixel_delta_v = viewport_v/(image_size.height as f64);
+
+            let viewport_top_left =
+                    look_from
+                        - w*focus_dist
+                        - viewport_u/2.0
+                        - viewport_v/2.0
+                        + (viewport_pixel_delta_u + viewport_pixel_delta_v)/2.0
+                    ;
+
+            (viewport_pixel_delta_u, viewport_pixel_delta_v, viewport_top_left)
+        }).unwrap_or((DVec3::ZERO, DVec3::ZERO, look_from));
+
+        let defocus_radius = if let Some(f_number) = self.f_number {
+            // The aperture diameter is the focal length divided by the
+            // f-number, converted from millimeters to scene units.
+            focal_length/(2.0*f_number*Self::MILLIMETERS_PER_UNIT)
+        } else {
+            focus_dist*(defocus_angle/2.0).tan()
+        };
         let defocus_disk_u = u*defocus_radius;
         let defocus_disk_v = v*defocus_radius;
 
-        Camera {
+        Ok(Camera {
             image_size,
 
-            background_color,
+            background,
 
             look_from,
             // look_at,
             // view_up,
             // defocus_angle,
             // field_of_view,
-            // focus_dist,
+            focus_dist,
+            aperture,
+            projection,
+            shutter,
+            spectral,
+
+            u,
+            v,
+            w,
 
             ray_max_bounces,
             samples_per_pixel,
@@ -155,7 +339,53 @@ impl CameraBuilder {
             viewport_pixel_delta_u,
             viewport_pixel_delta_v,
             viewport_top_left,
+        })
+    }
+}
+
+impl CameraBuilder {
+    pub fn autofocus(
+        &mut self,
+        hitable: &impl Hitable,
+        pixel: Option<(usize, usize)>,
+    ) -> Result<&mut Self> {
+        // Trace a single ray through the center of the pixel with a pinhole
+        // camera and focus on the first hit if any.
+        let camera = Self {
+            defocus_angle: 0.,
+            f_number: None,
+            samples_per_pixel: 1,
+            ..self.clone()
+        }.try_build()?;
+
+        let (x, y) = pixel.unwrap_or((
+            self.image_size.width/2,
+            self.image_size.height/2,
+        ));
+
+        if x >= self.image_size.width || y >= self.image_size.height {
+            return Err(anyhow!(
+                "autofocus pixel ({x}, {y}) is outside of the {}x{} image",
+                self.image_size.width,
+                self.image_size.height,
+            ));
+        }
+
+        let mut rng = ChaCha8Rng::seed_from_u64(0);
+
+        let hit = camera
+            .get_ray(x as u32, y as u32, &mut rng)
+            .and_then(|ray| {
+                hitable
+                    .hit(&ray, Interval::new(0.001, f64::INFINITY))
+                    .map(|hit| (ray, hit))
+            });
+
+        if let Some((ray, hit)) = hit {
+            self.with_focus_dist((hit.point - ray.get_origin()).dot(-camera.w));
         }
+
+        Ok(self)
     }
 }
 
@@ -171,8 +401,15 @@ impl CameraBuilder {
 
     pub const DEFAULT_DEFOCUS_ANGLE: f64 = 0.;
     pub const DEFAULT_FIELD_OF_VIEW: f64 = PI/2.;
-    pub const DEFAULT_FOCAL_LENGTH: f64 = 1.0;
+    pub const DEFAULT_SENSOR_WIDTH: f64 = 36.0;
     pub const DEFAULT_FOCUS_DISTANCE: f64 = 1.0;
+    pub const DEFAULT_VIEW_HEIGHT: f64 = 2.0;
+
+    // Focal lengths and sensor sizes are given in millimeters while scene
+    // units are assumed to be meters.
+    pub const MILLIMETERS_PER_UNIT: f64 = 1000.0;
+
+    pub const DEFAULT_SHUTTER: Interval = Interval::new(0.0, 1.0);
 
     pub const DEFAULT_RAY_MAX_BOUNCES: usize = 10;
     pub const DEFAULT_SAMPLES_PER_PIXEL: usize = 10;
@@ -186,7 +423,7 @@ impl Default for CameraBuilder {
                 width: Self::DEFAULT_IMAGE_WIDTH,
             },
 
-            background_color: Self::DEFAULT_BACKGROUND_COLOR,
+            background: Arc::new(ConstantBackground::new(Self::DEFAULT_BACKGROUND_COLOR)),
 
             look_at: Self::DEFAULT_LOOK_AT,
             look_from: Self::DEFAULT_LOOK_FROM,
@@ -194,7 +431,15 @@ impl Default for CameraBuilder {
 
             defocus_angle: Self::DEFAULT_DEFOCUS_ANGLE,
             focus_dist: Self::DEFAULT_FOCUS_DISTANCE,
+            focus_point: None,
             field_of_view: Self::DEFAULT_FIELD_OF_VIEW,
+            focal_length: None,
+            sensor_width: Self::DEFAULT_SENSOR_WIDTH,
+            f_number: None,
+            aperture: Aperture::default(),
+            projection: Projection::default(),
+            shutter: Self::DEFAULT_SHUTTER,
+            spectral: false,
 
             ray_max_bounces: Self::DEFAULT_RAY_MAX_BOUNCES,
             samples_per_pixel: Self::DEFAULT_SAMPLES_PER_PIXEL,
@@ -202,18 +447,26 @@ impl Default for CameraBuilder {
     }
 }
 
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Debug)]
 pub struct Camera {
     image_size: ImageSize,
 
-    background_color: DVec3,
+    background: Arc<dyn Background + Send + Sync>,
 
     look_from: DVec3,
     // look_at: DVec3,
     // view_up: DVec3,
     // defocus_angle: f64,
-    // focus_dist: f64,
     // field_of_view: f64,
+    focus_dist: f64,
+    aperture: Aperture,
+    projection: Projection,
+    shutter: Interval,
+    spectral: bool,
+
+    u: DVec3,
+    v: DVec3,
+    w: DVec3,
 
     ray_max_bounces: usize,
     samples_per_pixel: usize,
@@ -235,10 +488,56 @@ impl Camera {
 impl Camera {
     fn defocus_disk_sample(
         &self,
+        center: DVec3,
         rng: &mut impl Rng,
     ) -> DVec3 {
-        let p = random_in_unit_disk(rng);
-        self.look_from + p.x*self.defocus_disk_u + p.y*self.defocus_disk_v
+        let p = self.aperture.sample(rng);
+        center + p.x*self.defocus_disk_u + p.y*self.defocus_disk_v
+    }
+
+    fn get_viewport_point(
+        &self,
+        x: u32,
+        y: u32,
+        offset: DVec2,
+    ) -> DVec3 {
+        self.viewport_top_left
+            + (x as f64 + offset.x)*self.viewport_pixel_delta_u
+            + (y as f64 + offset.y)*self.viewport_pixel_delta_v
+    }
+
+    fn get_image_coordinates(
+        &self,
+        x: u32,
+        y: u32,
+        offset: DVec2,
+    ) -> DVec2 {
+        DVec2::new(
+            (x as f64 + 0.5 + offset.x)/(self.image_size.width as f64),
+            (y as f64 + 0.5 + offset.y)/(self.image_size.height as f64),
+        )
+    }
+
+    // Width of a pixel on the lens plane, only orthographic rays start with
+    // a non zero width.
+    fn get_pixel_cone_width(&self) -> f64 {
+        match self.projection {
+            Projection::Orthographic { .. } => self.viewport_pixel_delta_u.length(),
+            _ => 0.0,
+        }
+    }
+
+    // Angle covered by a pixel.
+    fn get_pixel_cone_spread(&self) -> f64 {
+        let width = self.image_size.width as f64;
+        let height = self.image_size.height as f64;
+
+        match self.projection {
+            Projection::Perspective => self.viewport_pixel_delta_u.length()/self.focus_dist,
+            Projection::Orthographic { .. } => 0.0,
+            Projection::Equirectangular => 2.0*PI/width,
+            Projection::Fisheye { field_of_view } => field_of_view/width.min(height),
+        }
     }
 
     fn get_ray(
@@ -246,62 +545,217 @@ impl Camera {
         x: u32,
         y: u32,
         rng: &mut impl Rng,
-    ) -> Ray {
+    ) -> Option<Ray> {
         let offset = if self.samples_per_pixel > 1 {
             DVec2::from_rng_ranged(rng, -0.5..=0.5)
         } else {
             DVec2::ZERO
         };
 
-        let point =
-            self.viewport_top_left
-                + (x as f64 + offset.x)*self.viewport_pixel_delta_u
-                + (y as f64 + offset.y)*self.viewport_pixel_delta_v
-            ;
+        let (origin, direction) = match self.projection {
+            Projection::Perspective => {
+                let point = self.get_viewport_point(x, y, offset);
+                let origin = self.defocus_disk_sample(self.look_from, rng);
+
+                (origin, point - origin)
+            },
+            Projection::Orthographic { .. } => {
+                // Rays are parallel to the view direction, each one starts
+                // from the lens plane right in front of its pixel.
+                let point = self.get_viewport_point(x, y, offset);
+                let origin = self.defocus_disk_sample(point + self.w*self.focus_dist, rng);
+
+                (origin, point - origin)
+            },
+            Projection::Equirectangular => {
+                let st = self.get_image_coordinates(x, y, offset);
+
+                let longitude = (st.x - 0.5)*2.0*PI;
+                let latitude = (0.5 - st.y)*PI;
+
+                let direction =
+                    latitude.cos()*longitude.sin()*self.u
+                        + latitude.sin()*self.v
+                        - latitude.cos()*longitude.cos()*self.w
+                    ;
+
+                (self.look_from, direction)
+            },
+            Projection::Fisheye { field_of_view } => {
+                // Equidistant fisheye, the image circle fits in the smallest
+                // dimension of the image.
+                let st = self.get_image_coordinates(x, y, offset);
+                let width = self.image_size.width as f64;
+                let height = self.image_size.height as f64;
+
+                let p = DVec2::new(
+                    (st.x - 0.5)*width,
+                    (0.5 - st.y)*height,
+                )/(width.min(height)/2.0);
+
+                let r = p.length();
+
+                if r > 1.0 {
+                    return None;
+                }
+
+                let theta = r*field_of_view/2.0;
+                let phi = p.y.atan2(p.x);
+
+                let direction =
+                    theta.sin()*(phi.cos()*self.u + phi.sin()*self.v)
+                        - theta.cos()*self.w
+                    ;
+
+                (self.look_from, direction)
+            },
+        };
+
+        let time = if self.shutter.size() > 0.0 {
+            rng.random_range(self.shutter.min..self.shutter.max)
+        } else {
+            self.shutter.min
+        };
+
+        let mut ray = Ray::new_at_time(origin, direction, time);
+
+        ray.with_cone(self.get_pixel_cone_width(), self.get_pixel_cone_spread());
+
+        if self.spectral {
+            ray.with_wavelength(Some(rng.random_range(WAVELENGTH_MIN..WAVELENGTH_MAX)));
+        }
+
+        Some(ray)
+    }
+
+    fn sample_background(
+        &self,
+        ray: &Ray,
+        hit_record: &HitRecord,
+        hitable: &impl Hitable,
+        rng: &mut impl Rng,
+    ) -> DVec3 {
+        let sample = self.background.sample(rng).and_then(|(direction, light_pdf)| {
+            hit_record.material
+                .eval(ray, hit_record, direction)
+                .map(|(value, scatter_pdf)| (direction, light_pdf, value, scatter_pdf))
+        });
+
+        match sample {
+            Some((direction, light_pdf, value, scatter_pdf)) if light_pdf > 0.0 => {
+                let shadow_ray = Ray::new_at_time(hit_record.point, direction, ray.get_time());
+
+                if hitable.hit(&shadow_ray, Interval::new(0.001, f64::INFINITY)).is_some() {
+                    return DVec3::ZERO;
+                }
+
+                let weight = power_heuristic(light_pdf, scatter_pdf);
+
+                weight*value*self.background.get_color(direction)/light_pdf
+            },
+            _ => DVec3::ZERO,
+        }
+    }
+
+    fn sample_lights(
+        &self,
+        ray: &Ray,
+        hit_record: &HitRecord,
+        hitable: &impl Hitable,
+        lights: &[Arc<dyn Light + Send + Sync>],
+    ) -> DVec3 {
+        lights.iter().filter_map(|light| {
+            let sample = light.sample(hit_record.point)?;
+            let (value, _) = hit_record.material.eval(ray, hit_record, sample.direction)?;
+
+            if value == DVec3::ZERO {
+                return None;
+            }
 
-        let origin = self.defocus_disk_sample(rng);
-        let direction = point - origin;
-        let time = rng.random_range(0.0..1.0);
+            let shadow_ray = Ray::new_at_time(hit_record.point, sample.direction, ray.get_time());
 
-        Ray::new_at_time(origin, direction, time)
+            if hitable.hit(&shadow_ray, Interval::new(0.001, sample.distance - 0.001)).is_some() {
+                return None;
+            }
+
+            Some(value*sample.radiance)
+        }).sum()
     }
 
     fn get_ray_color(
         &self,
         ray: &Ray,
         ray_bounce: usize,
+        scatter_pdf: Option<f64>,
         hitable: &impl Hitable,
+        lights: &[Arc<dyn Light + Send + Sync>],
         rng: &mut impl Rng,
     ) -> DVec3 {
         if ray_bounce >= self.ray_max_bounces {
             return DVec3::ZERO;
         }
 
-        match hitable.hit(ray, Interval::new(0.001, INFINITY)).as_ref() {
+        match hitable.hit(ray, Interval::new(0.001, f64::INFINITY)).as_ref() {
             Some(hit_record) => {
                 let material = hit_record.material.clone();
                 let emitted = material.emit(ray, hit_record);
 
+                // Paths sampling the background and the lights directly must
+                // not be longer than the scattered ones.
+                let direct = if ray_bounce + 1 < self.ray_max_bounces {
+                    self.sample_background(ray, hit_record, hitable, rng)
+                        + self.sample_lights(ray, hit_record, hitable, lights)
+                } else {
+                    DVec3::ZERO
+                };
+
                 material.scatter(ray, hit_record, rng)
                     .map(|(mut scattered_ray, color)| {
-                        scattered_ray.bounce();
-
-                        emitted + color*self.get_ray_color(
+                        // Footprints keep growing at the same rate after a
+                        // bounce.
+                        scattered_ray
+                            .bounce()
+                            .with_wavelength(ray.get_wavelength())
+                            .with_cone(ray.get_cone_width(hit_record.t), ray.get_cone_spread());
+
+                        let scatter_pdf = if scattered_ray.is_specular() {
+                            None
+                        } else {
+                            material
+                                .eval(ray, hit_record, scattered_ray.get_direction())
+                                .map(|(_, pdf)| pdf)
+                        };
+
+                        emitted + direct + color*self.get_ray_color(
                             &scattered_ray,
                             ray_bounce + 1,
+                            scatter_pdf,
                             hitable,
+                            lights,
                             rng
                         )
                     })
-                    .unwrap_or(emitted)
+                    .unwrap_or(emitted + direct)
+            },
+            _ => {
+                let direction = ray.get_direction().normalize();
+                let color = self.background.get_color(direction);
+
+                // Weight the contribution against the one of the direct
+                // sampling of the background.
+                if let Some(scatter_pdf) = scatter_pdf {
+                    color*power_heuristic(scatter_pdf, self.background.pdf(direction))
+                } else {
+                    color
+                }
             },
-            _ => self.background_color,
         }
     }
 
     pub fn render<T, P>(
         &self,
         hitable: &T,
+        lights: &[Arc<dyn Light + Send + Sync>],
         progress: Option<P>,
     ) -> Rgb32FImage
         where
@@ -323,9 +777,17 @@ impl Camera {
                 let y = n/width;
 
                 let s = (0..sample_per_pixel).map(|_| {
-                    let ray = self.get_ray(x, y, &mut rng);
-
-                    self.get_ray_color(&ray, 0, hitable, &mut rng)
+                    self.get_ray(x, y, &mut rng)
+                        .map(|ray| {
+                            let color = self.get_ray_color(&ray, 0, None, hitable, lights, &mut rng);
+
+                            // Spectral samples are converted back to RGB by
+                            // the response of the film at their wavelength.
+                            ray.get_wavelength()
+                                .map(|wavelength| color*spectral_weight(wavelength))
+                                .unwrap_or(color)
+                        })
+                        .unwrap_or(DVec3::ZERO)
                 }).sum::<DVec3>();
 
                 let color = s/(sample_per_pixel as f64);
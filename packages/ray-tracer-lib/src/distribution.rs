@@ -0,0 +1,106 @@
+use glam::DVec2;
+
+use rand::{
+    Rng,
+    RngCore,
+};
+
+#[derive(Clone, Debug)]
+struct Distribution1D {
+    cdf: Vec<f64>,
+}
+
+impl Distribution1D {
+    fn new(weights: &[f64]) -> Self {
+        let mut cdf = Vec::with_capacity(weights.len() + 1);
+
+        cdf.push(0.0);
+        for weight in weights {
+            cdf.push(cdf.last().unwrap() + weight.max(0.0));
+        }
+
+        let total = *cdf.last().unwrap();
+        let count = weights.len() as f64;
+
+        cdf.iter_mut().enumerate().for_each(|(i, c)| {
+            *c = if total > 0.0 { *c/total } else { (i as f64)/count };
+        });
+
+        Self { cdf }
+    }
+
+    fn len(&self) -> usize {
+        self.cdf.len() - 1
+    }
+
+    fn probability(&self, i: usize) -> f64 {
+        self.cdf[i + 1] - self.cdf[i]
+    }
+
+    fn sample(&self, u: f64) -> usize {
+        self.cdf
+            .partition_point(|c| *c <= u)
+            .saturating_sub(1)
+            .min(self.len() - 1)
+    }
+}
+
+// Piecewise constant distribution over the pixels of an image, texture
+// coordinates have v going up the image.
+#[derive(Clone, Debug)]
+pub(crate) struct Distribution2D {
+    rows: Distribution1D,
+    columns: Vec<Distribution1D>,
+}
+
+impl Distribution2D {
+    pub(crate) fn from_fn<F>(width: u32, height: u32, weight: F) -> Self
+        where
+            F: Fn(u32, u32) -> f64,
+    {
+        let mut row_weights = Vec::with_capacity(height as usize);
+        let mut columns = Vec::with_capacity(height as usize);
+
+        for y in 0..height {
+            let weights = (0..width)
+                .map(|x| weight(x, y))
+                .collect::<Vec<_>>();
+
+            row_weights.push(weights.iter().sum());
+            columns.push(Distribution1D::new(&weights));
+        }
+
+        Self {
+            rows: Distribution1D::new(&row_weights),
+            columns,
+        }
+    }
+
+    fn width(&self) -> usize {
+        self.columns[0].len()
+    }
+
+    fn height(&self) -> usize {
+        self.rows.len()
+    }
+
+    pub(crate) fn sample(&self, rng: &mut dyn RngCore) -> DVec2 {
+        let y = self.rows.sample(rng.random());
+        let x = self.columns[y].sample(rng.random());
+
+        DVec2::new(
+            (x as f64 + rng.random::<f64>())/(self.width() as f64),
+            1.0 - (y as f64 + rng.random::<f64>())/(self.height() as f64),
+        )
+    }
+
+    pub(crate) fn pdf(&self, uv: DVec2) -> f64 {
+        let width = self.width();
+        let height = self.height();
+
+        let x = ((uv.x*(width as f64)) as usize).min(width - 1);
+        let y = (((1.0 - uv.y)*(height as f64)) as usize).min(height - 1);
+
+        self.rows.probability(y)*self.columns[y].probability(x)*((width*height) as f64)
+    }
+}
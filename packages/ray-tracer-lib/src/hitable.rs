@@ -17,8 +17,15 @@ pub struct HitRecord {
     pub material: Arc<dyn Material + Send + Sync>,
     pub normal: DVec3,
     pub point: DVec3,
+    // Unit tangent following increasing texture coordinate u.
+    pub tangent: DVec3,
     pub t: f64,
     pub texture_coordinates: DVec2,
+    // Width of the ray footprint in texture coordinates.
+    pub footprint: f64,
+    // World lengths of the derivatives of the point along the texture
+    // coordinates u and v.
+    pub uv_lengths: DVec2,
 }
 
 impl Debug for HitRecord {
@@ -28,18 +35,22 @@ impl Debug for HitRecord {
             .field("front_face", &self.front_face)
             .field("normal", &self.normal)
             .field("point", &self.point)
+            .field("tangent", &self.tangent)
             .field("t", &self.t)
             .field("texture_coordinates", &self.texture_coordinates)
+            .field("footprint", &self.footprint)
+            .field("uv_lengths", &self.uv_lengths)
             .finish()
     }
 }
 
 impl HitRecord {
-    pub fn new_with_uv(
+    pub fn new_with_tangent(
         ray: &Ray,
         material: Arc<dyn Material + Send + Sync>,
         point: DVec3,
         outward_normal: DVec3,
+        tangent: DVec3,
         texture_coordinates: DVec2,
         t: f64,
     ) -> Self {
@@ -53,11 +64,33 @@ impl HitRecord {
             material,
             normal,
             point,
+            tangent,
             texture_coordinates,
             t,
+            footprint: 0.0,
+            uv_lengths: DVec2::ONE,
         }
     }
 
+    pub fn new_with_uv(
+        ray: &Ray,
+        material: Arc<dyn Material + Send + Sync>,
+        point: DVec3,
+        outward_normal: DVec3,
+        texture_coordinates: DVec2,
+        t: f64,
+    ) -> Self {
+        Self::new_with_tangent(
+            ray,
+            material,
+            point,
+            outward_normal,
+            outward_normal.any_orthonormal_vector(),
+            texture_coordinates,
+            t,
+        )
+    }
+
     pub fn new(
         ray: &Ray,
         material: Arc<dyn Material + Send + Sync>,
@@ -42,7 +42,7 @@ impl Interval {
     }
 
     pub const fn is_empty(&self) -> bool {
-        return self.min > self.max
+        self.min > self.max
     }
 
     pub const fn pad(&self, padding: f64) -> Self {
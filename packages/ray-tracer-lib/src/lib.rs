@@ -1,12 +1,17 @@
 pub mod aabb;
+pub mod aperture;
+pub mod backgrounds;
 pub mod camera;
+mod distribution;
 pub mod hitable;
 pub mod image;
 pub mod interval;
+pub mod lights;
 pub mod materials;
 pub mod objects;
 pub mod prelude;
 pub mod ray;
 pub mod scene;
+pub mod spectrum;
 pub mod textures;
 pub mod vector;
@@ -0,0 +1,70 @@
+use glam::DVec3;
+
+use super::light::{
+    Light,
+    LightSample,
+};
+
+#[derive(Clone, Copy, Debug)]
+pub struct DirectionalLight {
+    // Unit direction toward the light.
+    direction: DVec3,
+    irradiance: DVec3,
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+pub struct DirectionalLightBuilder {
+    direction: Option<DVec3>,
+    color: Option<DVec3>,
+    intensity: Option<f64>,
+}
+
+impl DirectionalLightBuilder {
+    // Direction in which the light travels.
+    pub fn with_direction(
+        &mut self,
+        value: Option<DVec3>,
+    ) -> &mut Self {
+        self.direction = value;
+        self
+    }
+
+    pub fn with_color(
+        &mut self,
+        value: Option<DVec3>,
+    ) -> &mut Self {
+        self.color = value;
+        self
+    }
+
+    pub fn with_intensity(
+        &mut self,
+        value: Option<f64>,
+    ) -> &mut Self {
+        self.intensity = value;
+        self
+    }
+
+    pub fn build(self) -> DirectionalLight {
+        let direction = self.direction
+            .and_then(DVec3::try_normalize)
+            .unwrap_or(DVec3::NEG_Y);
+        let color = self.color.unwrap_or(DVec3::ONE);
+        let intensity = self.intensity.unwrap_or(1.0);
+
+        DirectionalLight {
+            direction: -direction,
+            irradiance: intensity*color,
+        }
+    }
+}
+
+impl Light for DirectionalLight {
+    fn sample(&self, _: DVec3) -> Option<LightSample> {
+        Some(LightSample {
+            direction: self.direction,
+            distance: f64::INFINITY,
+            radiance: self.irradiance,
+        })
+    }
+}
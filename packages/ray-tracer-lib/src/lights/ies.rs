@@ -0,0 +1,187 @@
+use std::f64::consts::{
+    FRAC_PI_2,
+    PI,
+};
+use std::fs;
+use std::path::Path;
+
+use anyhow::{
+    anyhow,
+    Result,
+};
+
+use glam::DVec3;
+
+// Photometric profile read from an IES LM-63 file. Candela values are
+// normalized by their maximum so the profile only shapes the emission. Only
+// type C photometry is supported.
+#[derive(Clone, Debug)]
+pub struct IesProfile {
+    vertical_angles: Vec<f64>,
+    horizontal_angles: Vec<f64>,
+    // One row of vertical samples per horizontal angle.
+    values: Vec<Vec<f64>>,
+}
+
+fn interpolate_index(angles: &[f64], angle: f64) -> (usize, usize, f64) {
+    let last = angles.len() - 1;
+
+    if angle <= angles[0] {
+        return (0, 0, 0.0);
+    }
+
+    if angle >= angles[last] {
+        return (last, last, 0.0);
+    }
+
+    let i = angles.partition_point(|a| *a <= angle).min(last) - 1;
+    let t = (angle - angles[i])/(angles[i + 1] - angles[i]);
+
+    (i, i + 1, t)
+}
+
+impl IesProfile {
+    pub fn try_from_path<P: AsRef<Path>>(path: P) -> Result<Self> {
+        Self::parse(&fs::read_to_string(path.as_ref())?)
+    }
+
+    pub fn parse(contents: &str) -> Result<Self> {
+        let mut lines = contents.lines();
+
+        // Keywords are skipped up to the TILT line.
+        let tilt = lines
+            .find_map(|line| line.trim().strip_prefix("TILT="))
+            .ok_or(anyhow!("IES: missing TILT line"))?
+            .trim()
+            .to_owned();
+
+        let mut numbers = lines
+            .flat_map(|line| line.split(|c: char| c.is_whitespace() || c == ','))
+            .filter(|token| !token.is_empty())
+            .map(|token| token.parse::<f64>().map_err(|_| anyhow!("IES: invalid number '{token}'")));
+
+        let mut next = || numbers.next().unwrap_or(Err(anyhow!("IES: unexpected end of file")));
+
+        if tilt == "INCLUDE" {
+            let _lamp_to_luminaire_geometry = next()?;
+            let count = next()? as usize;
+
+            for _ in 0..2*count {
+                next()?;
+            }
+        } else if tilt != "NONE" {
+            return Err(anyhow!("IES: external TILT files are not supported"));
+        }
+
+        let _lamp_count = next()?;
+        let _lumens_per_lamp = next()?;
+        let _candela_multiplier = next()?;
+        let vertical_count = next()? as usize;
+        let horizontal_count = next()? as usize;
+        let photometric_type = next()?;
+        let _units_type = next()?;
+        let _width = next()?;
+        let _length = next()?;
+        let _height = next()?;
+        let _ballast_factor = next()?;
+        let _future_use = next()?;
+        let _input_watts = next()?;
+
+        if photometric_type != 1.0 {
+            return Err(anyhow!("IES: only type C photometry is supported"));
+        }
+
+        if vertical_count == 0 || horizontal_count == 0 {
+            return Err(anyhow!("IES: empty candela table"));
+        }
+
+        let vertical_angles = (0..vertical_count)
+            .map(|_| next())
+            .collect::<Result<Vec<_>>>()?;
+        let horizontal_angles = (0..horizontal_count)
+            .map(|_| next())
+            .collect::<Result<Vec<_>>>()?;
+        let mut values = (0..horizontal_count)
+            .map(|_| (0..vertical_count).map(|_| next()).collect::<Result<Vec<_>>>())
+            .collect::<Result<Vec<_>>>()?;
+
+        let max_value = values
+            .iter()
+            .flatten()
+            .copied()
+            .fold(0.0, f64::max);
+
+        if max_value > 0.0 {
+            values.iter_mut().flatten().for_each(|value| *value /= max_value);
+        }
+
+        Ok(Self {
+            vertical_angles,
+            horizontal_angles,
+            values,
+        })
+    }
+
+    fn get_horizontal_angle(&self, angle: f64) -> f64 {
+        // The range covered by the horizontal angles tells which symmetry
+        // the luminaire has.
+        let last = *self.horizontal_angles.last().unwrap();
+
+        if last <= 0.0 {
+            0.0
+        } else if last <= 90.0 {
+            let angle = angle%180.0;
+            if angle > 90.0 { 180.0 - angle } else { angle }
+        } else if last <= 180.0 {
+            if angle > 180.0 { 360.0 - angle } else { angle }
+        } else {
+            angle
+        }
+    }
+
+    // Returns the relative intensity for a direction given in the luminaire
+    // frame, where the luminaire points toward +Z (the nadir of the profile)
+    // and horizontal angles are measured from +X.
+    pub fn get_intensity(&self, direction: DVec3) -> f64 {
+        let direction = direction.normalize();
+
+        let vertical = direction.z.clamp(-1.0, 1.0).acos().to_degrees();
+        let horizontal = f64::atan2(direction.y, direction.x).to_degrees().rem_euclid(360.0);
+        let horizontal = self.get_horizontal_angle(horizontal);
+
+        let (v0, v1, tv) = interpolate_index(&self.vertical_angles, vertical);
+        let (h0, h1, th) = interpolate_index(&self.horizontal_angles, horizontal);
+
+        let value = |h: usize| {
+            let row = &self.values[h];
+            (1.0 - tv)*row[v0] + tv*row[v1]
+        };
+
+        (1.0 - th)*value(h0) + th*value(h1)
+    }
+
+    // Integral of the relative intensity times the cosine to the nadir over
+    // the hemisphere around it, π for a uniform profile.
+    pub fn get_projected_integral(&self) -> f64 {
+        const THETA_STEPS: usize = 90;
+        const PHI_STEPS: usize = 180;
+
+        let d_theta = FRAC_PI_2/(THETA_STEPS as f64);
+        let d_phi = 2.0*PI/(PHI_STEPS as f64);
+
+        (0..THETA_STEPS)
+            .flat_map(|i| (0..PHI_STEPS).map(move |j| (i, j)))
+            .map(|(i, j)| {
+                let theta = (i as f64 + 0.5)*d_theta;
+                let phi = (j as f64 + 0.5)*d_phi;
+                let direction = DVec3::new(
+                    theta.sin()*phi.cos(),
+                    theta.sin()*phi.sin(),
+                    theta.cos(),
+                );
+
+                self.get_intensity(direction)*theta.cos()*theta.sin()*d_theta*d_phi
+            })
+            .sum()
+    }
+}
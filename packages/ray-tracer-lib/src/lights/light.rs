@@ -0,0 +1,17 @@
+use std::fmt::Debug;
+
+use glam::DVec3;
+
+#[derive(Clone, Copy, Debug)]
+pub struct LightSample {
+    // Unit direction from the shaded point toward the light.
+    pub direction: DVec3,
+    pub distance: f64,
+    pub radiance: DVec3,
+}
+
+pub trait Light: Debug {
+    // Delta lights are sampled with probability one, the returned radiance
+    // is the light incoming at point from the sampled direction.
+    fn sample(&self, point: DVec3) -> Option<LightSample>;
+}
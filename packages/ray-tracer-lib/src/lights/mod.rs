@@ -0,0 +1,11 @@
+mod light;
+mod directional;
+mod ies;
+mod point;
+mod spot;
+
+pub use light::*;
+pub use directional::*;
+pub use ies::*;
+pub use point::*;
+pub use spot::*;
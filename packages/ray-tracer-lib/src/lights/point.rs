@@ -0,0 +1,75 @@
+use glam::DVec3;
+
+use super::light::{
+    Light,
+    LightSample,
+};
+
+#[derive(Clone, Copy, Debug)]
+pub struct PointLight {
+    position: DVec3,
+    intensity: DVec3,
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+pub struct PointLightBuilder {
+    position: Option<DVec3>,
+    color: Option<DVec3>,
+    intensity: Option<f64>,
+}
+
+impl PointLightBuilder {
+    pub fn with_position(
+        &mut self,
+        value: Option<DVec3>,
+    ) -> &mut Self {
+        self.position = value;
+        self
+    }
+
+    pub fn with_color(
+        &mut self,
+        value: Option<DVec3>,
+    ) -> &mut Self {
+        self.color = value;
+        self
+    }
+
+    pub fn with_intensity(
+        &mut self,
+        value: Option<f64>,
+    ) -> &mut Self {
+        self.intensity = value;
+        self
+    }
+
+    pub fn build(self) -> PointLight {
+        let position = self.position.unwrap_or(DVec3::ZERO);
+        let color = self.color.unwrap_or(DVec3::ONE);
+        let intensity = self.intensity.unwrap_or(1.0);
+
+        PointLight {
+            position,
+            intensity: intensity*color,
+        }
+    }
+}
+
+impl Light for PointLight {
+    fn sample(&self, point: DVec3) -> Option<LightSample> {
+        let to_light = self.position - point;
+        let distance_squared = to_light.length_squared();
+
+        if distance_squared <= 0.0 {
+            return None;
+        }
+
+        let distance = distance_squared.sqrt();
+
+        Some(LightSample {
+            direction: to_light/distance,
+            distance,
+            radiance: self.intensity/distance_squared,
+        })
+    }
+}
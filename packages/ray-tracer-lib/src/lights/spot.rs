@@ -0,0 +1,137 @@
+use std::f64::consts::FRAC_PI_4;
+
+use glam::DVec3;
+
+use super::light::{
+    Light,
+    LightSample,
+};
+
+#[derive(Clone, Copy, Debug)]
+pub struct SpotLight {
+    position: DVec3,
+    direction: DVec3,
+    intensity: DVec3,
+    cos_cone_angle: f64,
+    cos_falloff_start: f64,
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SpotLightBuilder {
+    position: Option<DVec3>,
+    look_at: Option<DVec3>,
+    color: Option<DVec3>,
+    intensity: Option<f64>,
+    cone_angle: Option<f64>,
+    falloff: Option<f64>,
+}
+
+impl SpotLightBuilder {
+    pub fn with_position(
+        &mut self,
+        value: Option<DVec3>,
+    ) -> &mut Self {
+        self.position = value;
+        self
+    }
+
+    pub fn with_look_at(
+        &mut self,
+        value: Option<DVec3>,
+    ) -> &mut Self {
+        self.look_at = value;
+        self
+    }
+
+    pub fn with_color(
+        &mut self,
+        value: Option<DVec3>,
+    ) -> &mut Self {
+        self.color = value;
+        self
+    }
+
+    pub fn with_intensity(
+        &mut self,
+        value: Option<f64>,
+    ) -> &mut Self {
+        self.intensity = value;
+        self
+    }
+
+    // Half angle of the light cone, in radians.
+    pub fn with_cone_angle(
+        &mut self,
+        value: Option<f64>,
+    ) -> &mut Self {
+        self.cone_angle = value;
+        self
+    }
+
+    // Fraction of the cone, from its edge, over which the light fades out.
+    pub fn with_falloff(
+        &mut self,
+        value: Option<f64>,
+    ) -> &mut Self {
+        self.falloff = value;
+        self
+    }
+
+    pub fn build(self) -> SpotLight {
+        let position = self.position.unwrap_or(DVec3::ZERO);
+        let look_at = self.look_at.unwrap_or(position - DVec3::Y);
+        let color = self.color.unwrap_or(DVec3::ONE);
+        let intensity = self.intensity.unwrap_or(1.0);
+        let cone_angle = self.cone_angle.unwrap_or(FRAC_PI_4);
+        let falloff = self.falloff.unwrap_or(0.2).clamp(0.0, 1.0);
+
+        let direction = (look_at - position).try_normalize().unwrap_or(DVec3::NEG_Y);
+
+        SpotLight {
+            position,
+            direction,
+            intensity: intensity*color,
+            cos_cone_angle: cone_angle.cos(),
+            cos_falloff_start: ((1.0 - falloff)*cone_angle).cos(),
+        }
+    }
+}
+
+impl SpotLight {
+    fn get_falloff(&self, cos_theta: f64) -> f64 {
+        if cos_theta >= self.cos_falloff_start {
+            1.0
+        } else if cos_theta <= self.cos_cone_angle {
+            0.0
+        } else {
+            let t = (cos_theta - self.cos_cone_angle)/(self.cos_falloff_start - self.cos_cone_angle);
+
+            t*t*(3.0 - 2.0*t)
+        }
+    }
+}
+
+impl Light for SpotLight {
+    fn sample(&self, point: DVec3) -> Option<LightSample> {
+        let to_light = self.position - point;
+        let distance_squared = to_light.length_squared();
+
+        if distance_squared <= 0.0 {
+            return None;
+        }
+
+        let distance = distance_squared.sqrt();
+        let direction = to_light/distance;
+        let falloff = self.get_falloff(-direction.dot(self.direction));
+
+        if falloff <= 0.0 {
+            return None;
+        }
+
+        Some(LightSample {
+            direction,
+            distance,
+            radiance: falloff*self.intensity/distance_squared,
+        })
+    }
+}
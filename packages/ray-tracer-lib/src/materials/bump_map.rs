@@ -0,0 +1,158 @@
+use std::sync::Arc;
+
+use glam::{
+    DVec2,
+    DVec3,
+};
+
+use rand::RngCore;
+
+use crate::hitable::HitRecord;
+use crate::ray::Ray;
+use crate::spectrum::luminance;
+use crate::textures::{
+    SolidColor,
+    Texture,
+};
+
+use super::lambertian::Lambertian;
+use super::material::Material;
+use super::normal_map::perturb_normal;
+
+// Step used to differentiate the height.
+const BUMP_DELTA: f64 = 1e-3;
+
+// Tilts the normal of the underlying material following the slope of a
+// height given by the luminance of a texture. Slopes are taken over a small
+// step along the texture coordinates and the matching distance on the
+// surface at once, so both image and solid textures can be used, image maps
+// must be decoded as linear.
+#[derive(Clone, Debug)]
+pub struct BumpMap {
+    material: Arc<dyn Material + Send + Sync>,
+    texture: Arc<dyn Texture + Send + Sync>,
+    strength: f64,
+}
+
+#[derive(Clone, Default)]
+pub struct BumpMapBuilder {
+    material: Option<Arc<dyn Material + Send + Sync>>,
+    texture: Option<Arc<dyn Texture + Send + Sync>>,
+    strength: Option<f64>,
+}
+
+impl BumpMapBuilder {
+    pub fn with_material(
+        &mut self,
+        value: Option<Arc<dyn Material + Send + Sync>>,
+    ) -> &mut Self {
+        self.material = value;
+        self
+    }
+
+    pub fn with_texture(
+        &mut self,
+        value: Option<Arc<dyn Texture + Send + Sync>>,
+    ) -> &mut Self {
+        self.texture = value;
+        self
+    }
+
+    // Height in scene units of a luminance of one.
+    pub fn with_strength(
+        &mut self,
+        value: Option<f64>,
+    ) -> &mut Self {
+        self.strength = value;
+        self
+    }
+
+    pub fn build(self) -> BumpMap {
+        BumpMap {
+            material: self.material.unwrap_or(Arc::new(Lambertian::default())),
+            texture: self.texture.unwrap_or(Arc::new(SolidColor::default())),
+            strength: self.strength.unwrap_or(1.0),
+        }
+    }
+}
+
+impl BumpMap {
+    fn get_height(&self, uv_coord: DVec2, point: DVec3, footprint: f64) -> f64 {
+        self.strength*luminance(self.texture.get_filtered_color(uv_coord, point, footprint))
+    }
+
+    fn get_hit(&self, hit: &HitRecord) -> HitRecord {
+        let n = if hit.front_face { hit.normal } else { -hit.normal };
+        let bitangent = n.cross(hit.tangent);
+
+        let uv = hit.texture_coordinates;
+        let height = self.get_height(uv, hit.point, hit.footprint);
+
+        // Steps backward near the upper bound of the texture coordinates.
+        let step_u = if uv.x + BUMP_DELTA > 1.0 { -BUMP_DELTA } else { BUMP_DELTA };
+        let step_v = if uv.y + BUMP_DELTA > 1.0 { -BUMP_DELTA } else { BUMP_DELTA };
+
+        // Distances on the surface covered by the steps, the derivative
+        // along u vanishes at the poles of a sphere.
+        let lengths = hit.uv_lengths.max(DVec2::splat(1e-6));
+        let step = DVec2::new(step_u, step_v)*lengths;
+
+        let du = self.get_height(
+            uv + DVec2::new(step_u, 0.0),
+            hit.point + step.x*hit.tangent,
+            hit.footprint,
+        ) - height;
+        let dv = self.get_height(
+            uv + DVec2::new(0.0, step_v),
+            hit.point + step.y*bitangent,
+            hit.footprint,
+        ) - height;
+
+        perturb_normal(hit, DVec3::new(-du/step.x, -dv/step.y, 1.0))
+    }
+}
+
+impl Material for BumpMap {
+    fn scatter(
+        &self,
+        ray: &Ray,
+        hit: &HitRecord,
+        rng: &mut dyn RngCore,
+    ) -> Option<(Ray, DVec3)> {
+        self.material.scatter(ray, &self.get_hit(hit), rng)
+    }
+
+    fn eval(
+        &self,
+        ray: &Ray,
+        hit: &HitRecord,
+        direction: DVec3,
+    ) -> Option<(DVec3, f64)> {
+        self.material.eval(ray, &self.get_hit(hit), direction)
+    }
+
+    fn is_cutout(
+        &self,
+        hit: &HitRecord,
+    ) -> bool {
+        self.material.is_cutout(hit)
+    }
+
+    fn emit(
+        &self,
+        ray: &Ray,
+        hit: &HitRecord,
+    ) -> DVec3 {
+        self.material.emit(ray, &self.get_hit(hit))
+    }
+
+    fn for_surface_area(
+        &self,
+        area: f64,
+    ) -> Option<Arc<dyn Material + Send + Sync>> {
+        Some(Arc::new(BumpMap {
+            material: self.material.for_surface_area(area)?,
+            ..self.clone()
+        }))
+    }
+}
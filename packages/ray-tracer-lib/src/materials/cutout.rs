@@ -0,0 +1,111 @@
+use std::sync::Arc;
+
+use glam::DVec3;
+
+use rand::RngCore;
+
+use crate::hitable::HitRecord;
+use crate::ray::Ray;
+use crate::textures::{
+    SolidColor,
+    Texture,
+};
+
+use super::lambertian::Lambertian;
+use super::material::Material;
+
+// Cuts out the surface of the underlying material where the opacity read
+// from a texture (e.g. the alpha channel of an image) is below a threshold.
+#[derive(Clone, Debug)]
+pub struct Cutout {
+    material: Arc<dyn Material + Send + Sync>,
+    texture: Arc<dyn Texture + Send + Sync>,
+    threshold: f64,
+}
+
+#[derive(Clone, Default)]
+pub struct CutoutBuilder {
+    material: Option<Arc<dyn Material + Send + Sync>>,
+    texture: Option<Arc<dyn Texture + Send + Sync>>,
+    threshold: Option<f64>,
+}
+
+impl CutoutBuilder {
+    pub fn with_material(
+        &mut self,
+        value: Option<Arc<dyn Material + Send + Sync>>,
+    ) -> &mut Self {
+        self.material = value;
+        self
+    }
+
+    pub fn with_texture(
+        &mut self,
+        value: Option<Arc<dyn Texture + Send + Sync>>,
+    ) -> &mut Self {
+        self.texture = value;
+        self
+    }
+
+    pub fn with_threshold(
+        &mut self,
+        value: Option<f64>,
+    ) -> &mut Self {
+        self.threshold = value;
+        self
+    }
+
+    pub fn build(self) -> Cutout {
+        Cutout {
+            material: self.material.unwrap_or(Arc::new(Lambertian::default())),
+            texture: self.texture.unwrap_or(Arc::new(SolidColor::default())),
+            threshold: self.threshold.unwrap_or(0.5),
+        }
+    }
+}
+
+impl Material for Cutout {
+    fn scatter(
+        &self,
+        ray: &Ray,
+        hit: &HitRecord,
+        rng: &mut dyn RngCore,
+    ) -> Option<(Ray, DVec3)> {
+        self.material.scatter(ray, hit, rng)
+    }
+
+    fn eval(
+        &self,
+        ray: &Ray,
+        hit: &HitRecord,
+        direction: DVec3,
+    ) -> Option<(DVec3, f64)> {
+        self.material.eval(ray, hit, direction)
+    }
+
+    fn is_cutout(
+        &self,
+        hit: &HitRecord,
+    ) -> bool {
+        self.texture.get_alpha(hit.texture_coordinates, hit.point) < self.threshold
+            || self.material.is_cutout(hit)
+    }
+
+    fn emit(
+        &self,
+        ray: &Ray,
+        hit: &HitRecord,
+    ) -> DVec3 {
+        self.material.emit(ray, hit)
+    }
+
+    fn for_surface_area(
+        &self,
+        area: f64,
+    ) -> Option<Arc<dyn Material + Send + Sync>> {
+        Some(Arc::new(Cutout {
+            material: self.material.for_surface_area(area)?,
+            ..self.clone()
+        }))
+    }
+}
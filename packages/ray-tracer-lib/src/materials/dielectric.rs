@@ -9,6 +9,7 @@ use crate::hitable::HitRecord;
 use crate::ray::Ray;
 
 use super::material::Material;
+use super::microfacet::*;
 
 fn reflectance(cosine: f64, refraction_index: f64) -> f64 {
     // Use Schlick's approximation for reflectance
@@ -18,20 +19,187 @@ fn reflectance(cosine: f64, refraction_index: f64) -> f64 {
     r0 + (1.0 - r0)*(1.0 - cosine).powi(5)
 }
 
+// Wavelength dependent index of refraction, wavelengths are given in
+// micrometers.
+#[derive(Clone, Copy, Debug)]
+pub enum Dispersion {
+    // n = a + b/λ²
+    Cauchy {
+        a: f64,
+        b: f64,
+    },
+    // n² = 1 + Σ bᵢλ²/(λ² - cᵢ)
+    Sellmeier {
+        b: [f64; 3],
+        c: [f64; 3],
+    },
+}
+
+impl Dispersion {
+    pub const BK7: Self = Self::Sellmeier {
+        b: [1.03961212, 0.231792344, 1.01046945],
+        c: [0.00600069867, 0.0200179144, 103.560653],
+    };
+    pub const DENSE_FLINT: Self = Self::Sellmeier {
+        b: [1.73759695, 0.313747346, 1.89878101],
+        c: [0.013188707, 0.0623068142, 155.23629],
+    };
+    pub const FUSED_SILICA: Self = Self::Sellmeier {
+        b: [0.6961663, 0.4079426, 0.8974794],
+        c: [0.0046791, 0.0135121, 97.934],
+    };
+
+    // Wavelength of the helium d line, used for rays not carrying any.
+    pub const REFERENCE_WAVELENGTH: f64 = 587.6;
+
+    // Returns the index of refraction at the given wavelength in nanometers.
+    pub fn get_refraction_index(&self, wavelength: f64) -> f64 {
+        let l2 = (wavelength*1e-3).powi(2);
+
+        match self {
+            Self::Cauchy { a, b } => a + b/l2,
+            Self::Sellmeier { b, c } => {
+                let n2 = 1.0 + b.iter()
+                    .zip(c.iter())
+                    .map(|(b, c)| b*l2/(l2 - c))
+                    .sum::<f64>();
+
+                n2.max(1.0).sqrt()
+            },
+        }
+    }
+}
+
 #[derive(Clone, Copy, Debug)]
 pub struct Dielectric {
     refraction_index: f64,
+    dispersion: Option<Dispersion>,
+    distribution: TrowbridgeReitz,
+    // Absorption coefficient of the medium enclosed by the surface.
+    absorption: Option<DVec3>,
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+pub struct DielectricBuilder {
+    refraction_index: Option<f64>,
+    dispersion: Option<Dispersion>,
+    roughness: Option<f64>,
+    absorption_color: Option<DVec3>,
+    absorption_distance: Option<f64>,
+}
+
+impl DielectricBuilder {
+    pub fn with_refraction_index(
+        &mut self,
+        value: Option<f64>,
+    ) -> &mut Self {
+        self.refraction_index = value;
+        self
+    }
+
+    // Overrides the refraction index.
+    pub fn with_dispersion(
+        &mut self,
+        value: Option<Dispersion>,
+    ) -> &mut Self {
+        self.dispersion = value;
+        self
+    }
+
+    pub fn with_roughness(
+        &mut self,
+        value: Option<f64>,
+    ) -> &mut Self {
+        self.roughness = value;
+        self
+    }
+
+    // Color a ray takes after traveling the absorption distance inside the
+    // medium.
+    pub fn with_absorption_color(
+        &mut self,
+        value: Option<DVec3>,
+    ) -> &mut Self {
+        self.absorption_color = value;
+        self
+    }
+
+    pub fn with_absorption_distance(
+        &mut self,
+        value: Option<f64>,
+    ) -> &mut Self {
+        self.absorption_distance = value;
+        self
+    }
+
+    pub fn build(self) -> Dielectric {
+        let refraction_index = self.refraction_index.unwrap_or(1.5);
+        let (alpha_x, alpha_y) = roughness_to_alpha(self.roughness.unwrap_or(0.0), 0.0);
+
+        // Beer-Lambert law, transmittance over a distance d is exp(-σd).
+        let absorption = self.absorption_color.map(|color| {
+            let distance = self.absorption_distance.unwrap_or(1.0).max(f64::EPSILON);
+
+            -color.clamp(DVec3::splat(1e-6), DVec3::ONE).map(f64::ln)/distance
+        });
+
+        Dielectric {
+            refraction_index,
+            dispersion: self.dispersion,
+            distribution: TrowbridgeReitz::new(alpha_x, alpha_y),
+            absorption,
+        }
+    }
 }
 
 impl Dielectric {
     pub fn new(refraction_index: f64) -> Self {
-        Self { refraction_index }
+        let mut dielectric_builder = DielectricBuilder::default();
+
+        dielectric_builder.with_refraction_index(Some(refraction_index));
+        dielectric_builder.build()
+    }
+
+    // Attenuation of a ray leaving the medium, hit from inside after
+    // traveling through it.
+    fn get_attenuation(
+        &self,
+        ray: &Ray,
+        hit_record: &HitRecord,
+    ) -> DVec3 {
+        match self.absorption {
+            Some(absorption) if !hit_record.front_face => {
+                let distance = hit_record.t*ray.get_direction().length();
+
+                (-absorption*distance).exp()
+            },
+            _ => DVec3::ONE,
+        }
+    }
+
+    fn get_refraction_index(&self, ray: &Ray) -> f64 {
+        match self.dispersion {
+            Some(dispersion) => dispersion.get_refraction_index(
+                ray.get_wavelength().unwrap_or(Dispersion::REFERENCE_WAVELENGTH),
+            ),
+            None => self.refraction_index,
+        }
+    }
+
+    fn get_eta(&self, ray: &Ray, hit_record: &HitRecord) -> f64 {
+        let refraction_index = self.get_refraction_index(ray);
+
+        if hit_record.front_face {
+            refraction_index
+        } else {
+            1.0/refraction_index
+        }
     }
 }
 
 impl Default for Dielectric {
     fn default() -> Self {
-        Self { refraction_index: 1.5 }
+        DielectricBuilder::default().build()
     }
 }
 
@@ -42,11 +210,34 @@ impl Material for Dielectric {
         hit_record: &HitRecord,
         rng: &mut dyn RngCore
     ) -> Option<(Ray, DVec3)> {
-        let ri = if hit_record.front_face {
-            1.0/self.refraction_index
-        } else {
-            self.refraction_index
-        };
+        let attenuation = self.get_attenuation(ray, hit_record);
+
+        if !self.distribution.is_smooth() {
+            let frame = Frame::from_hit(hit_record);
+            let wo = frame.to_local(-ray.get_direction().normalize());
+            let eta = self.get_eta(ray, hit_record);
+
+            let wi = sample_dielectric(
+                &self.distribution,
+                wo,
+                eta,
+                rng.random(),
+                rng.random(),
+                rng.random(),
+            )?;
+            let (value, pdf) = eval_dielectric(&self.distribution, wo, wi, eta);
+
+            if pdf <= 0.0 {
+                return None;
+            }
+
+            return Some((
+                Ray::new_at_time(hit_record.point, frame.to_world(wi), ray.get_time()),
+                attenuation*value/pdf,
+            ));
+        }
+
+        let ri = 1.0/self.get_eta(ray, hit_record);
 
         let unit_direction = ray.get_direction().normalize();
 
@@ -62,7 +253,25 @@ impl Material for Dielectric {
 
         Some((
             Ray::new_at_time(hit_record.point, scatter_direction, ray.get_time()),
-            DVec3::ONE,
+            attenuation,
         ))
     }
+
+    fn eval(
+        &self,
+        ray: &Ray,
+        hit_record: &HitRecord,
+        direction: DVec3,
+    ) -> Option<(DVec3, f64)> {
+        if self.distribution.is_smooth() {
+            return None;
+        }
+
+        let frame = Frame::from_hit(hit_record);
+        let wo = frame.to_local(-ray.get_direction().normalize());
+        let wi = frame.to_local(direction.normalize());
+        let (value, pdf) = eval_dielectric(&self.distribution, wo, wi, self.get_eta(ray, hit_record));
+
+        Some((self.get_attenuation(ray, hit_record)*value, pdf))
+    }
 }
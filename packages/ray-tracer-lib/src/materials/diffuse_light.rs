@@ -1,12 +1,15 @@
+use std::f64::consts::PI;
 use std::sync::Arc;
 
 use glam::DVec3;
 
 use crate::prelude::{
     HitRecord,
+    IesProfile,
     Ray,
     SolidColor,
     Texture,
+    blackbody_color,
 };
 
 use super::material::Material;
@@ -14,12 +17,24 @@ use super::material::Material;
 #[derive(Clone, Debug)]
 pub struct DiffuseLight {
     intensity: f64,
+    tint: DVec3,
+    power: Option<f64>,
+    two_sided: bool,
+    profile: Option<Arc<IesProfile>>,
+    profile_axis: Option<DVec3>,
+    profile_up: Option<DVec3>,
     texture: Arc<dyn Texture + Send + Sync>,
 }
 
 #[derive(Clone, Debug, Default)]
 pub struct DiffuseLightBuilder {
     intensity: Option<f64>,
+    temperature: Option<f64>,
+    power: Option<f64>,
+    two_sided: Option<bool>,
+    profile: Option<Arc<IesProfile>>,
+    profile_axis: Option<DVec3>,
+    profile_up: Option<DVec3>,
     texture: Option<Arc<dyn Texture + Send + Sync>>,
 }
 
@@ -29,6 +44,13 @@ impl DiffuseLightBuilder {
         self
     }
 
+    // Tints the emission with the color of a black body at the given
+    // temperature in kelvins.
+    pub fn with_temperature(&mut self, temperature: f64) -> &mut Self {
+        self.temperature.replace(temperature);
+        self
+    }
+
     pub fn with_texture(
         &mut self,
         texture: Arc<dyn Texture + Send + Sync>,
@@ -45,17 +67,109 @@ impl DiffuseLightBuilder {
         self
     }
 
+    // Total power emitted in watts, takes precedence over the intensity
+    // once the light is given the total area of all the surfaces it is
+    // applied to in the scene with `for_surface_area`.
+    pub fn with_power(
+        &mut self,
+        power: f64,
+    ) -> &mut Self {
+        self.power.replace(power);
+        self
+    }
+
+    pub fn with_two_sided(
+        &mut self,
+        two_sided: bool,
+    ) -> &mut Self {
+        self.two_sided.replace(two_sided);
+        self
+    }
+
+    // Angular distribution of the emission.
+    pub fn with_profile(
+        &mut self,
+        profile: Arc<IesProfile>,
+    ) -> &mut Self {
+        self.profile.replace(profile);
+        self
+    }
+
+    // World direction the profile nadir points to, the surface normal is
+    // used when not given.
+    pub fn with_profile_axis(
+        &mut self,
+        axis: DVec3,
+    ) -> &mut Self {
+        self.profile_axis = axis.try_normalize();
+        self
+    }
+
+    // World direction of the C0 plane of the profile, horizontal angles are
+    // measured from it around the profile axis. The tangent of the surface
+    // is used when not given.
+    pub fn with_profile_up(
+        &mut self,
+        up: DVec3,
+    ) -> &mut Self {
+        self.profile_up = up.try_normalize();
+        self
+    }
+
     pub fn build(
         &self,
     ) -> DiffuseLight {
         let intensity = self.intensity.unwrap_or(4.0);
+        let tint = self.temperature.map(blackbody_color).unwrap_or(DVec3::ONE);
+        let power = self.power;
+        let two_sided = self.two_sided.unwrap_or(true);
+        let profile = self.profile.clone();
+        let profile_axis = self.profile_axis;
+        let profile_up = self.profile_up;
         let texture = if let Some(texture) = &self.texture {
             texture.clone()
         } else {
             Arc::new(SolidColor::default())
         };
 
-        DiffuseLight { intensity, texture }
+        DiffuseLight {
+            intensity,
+            tint,
+            power,
+            two_sided,
+            profile,
+            profile_axis,
+            profile_up,
+            texture,
+        }
+    }
+}
+
+impl DiffuseLight {
+    fn get_profile_intensity(
+        &self,
+        ray: &Ray,
+        hit: &HitRecord,
+    ) -> f64 {
+        if let Some(profile) = self.profile.as_ref() {
+            let axis = self.profile_axis.unwrap_or(hit.normal);
+            // The C0 reference is projected on the plane orthogonal to the
+            // axis, any direction is taken when they are aligned.
+            let up = self.profile_up.unwrap_or(hit.tangent);
+            let u = (up - up.dot(axis)*axis)
+                .try_normalize()
+                .unwrap_or_else(|| axis.any_orthonormal_vector());
+            let v = axis.cross(u);
+            let direction = -ray.get_direction().normalize();
+
+            profile.get_intensity(DVec3::new(
+                direction.dot(u),
+                direction.dot(v),
+                direction.dot(axis),
+            ))
+        } else {
+            1.0
+        }
     }
 }
 
@@ -65,12 +179,41 @@ impl Material for DiffuseLight {
         ray: &Ray,
         hit: &HitRecord,
     ) -> DVec3 {
-        let k = if ray.get_bounce() > 0 {
-            self.intensity
-        } else {
-            1.0
-        };
+        if !(self.two_sided || hit.front_face) {
+            return DVec3::ZERO;
+        }
+
+        let k = self.intensity*self.get_profile_intensity(ray, hit);
+
+        k*self.tint*self.texture.get_filtered_color(hit.texture_coordinates, hit.point, hit.footprint)
+    }
+
+    fn for_surface_area(
+        &self,
+        area: f64,
+    ) -> Option<Arc<dyn Material + Send + Sync>> {
+        let power = self.power?;
+
+        if area <= 0.0 {
+            return None;
+        }
+
+        // A lambertian emitter of radiance L and area A emits a power of
+        // πLA through each of its emitting sides, a profile replaces π with
+        // its own integral assuming its axis follows the surface normal.
+        let sides = if self.two_sided { 2.0 } else { 1.0 };
+        let integral = self.profile
+            .as_ref()
+            .map_or(PI, |profile| profile.get_projected_integral());
+
+        if integral <= 0.0 {
+            return None;
+        }
 
-        k*self.texture.get_color(hit.texture_coordinates, hit.point)
+        Some(Arc::new(DiffuseLight {
+            intensity: power/(sides*integral*area),
+            power: None,
+            ..self.clone()
+        }))
     }
 }
@@ -1,3 +1,4 @@
+use std::f64::consts::PI;
 use std::sync::Arc;
 
 use glam::DVec3;
@@ -42,7 +43,7 @@ impl Material for Lambertian {
         hit: &HitRecord,
         rng: &mut dyn RngCore
     ) -> Option<(Ray, DVec3)> {
-        let mut scatter_direction = hit.normal + random_in_unit_sphere(rng);
+        let mut scatter_direction = hit.normal + random_unit_vector(rng);
 
         if scatter_direction.almost_zero(1e-8) {
             scatter_direction = hit.normal
@@ -50,7 +51,23 @@ impl Material for Lambertian {
 
         Some((
             Ray::new_at_time(hit.point, scatter_direction, ray.get_time()),
-            self.texture.get_color(hit.texture_coordinates, hit.point),
+            self.texture.get_filtered_color(hit.texture_coordinates, hit.point, hit.footprint),
         ))
     }
+
+    fn eval(
+        &self,
+        _ray: &Ray,
+        hit: &HitRecord,
+        direction: DVec3,
+    ) -> Option<(DVec3, f64)> {
+        let cosine = hit.normal.dot(direction.normalize());
+
+        if cosine > 0.0 {
+            let color = self.texture.get_filtered_color(hit.texture_coordinates, hit.point, hit.footprint);
+            Some((color*cosine/PI, cosine/PI))
+        } else {
+            Some((DVec3::ZERO, 0.0))
+        }
+    }
 }
@@ -1,4 +1,5 @@
 use std::fmt::Debug;
+use std::sync::Arc;
 
 use glam::DVec3;
 
@@ -17,6 +18,27 @@ pub trait Material: Debug {
         None
     }
 
+    // Returns the BSDF value times the cosine term for the given scattered
+    // direction and the probability density `scatter` samples it with.
+    // Materials which cannot be evaluated (e.g. specular ones) return None.
+    fn eval(
+        &self,
+        _ray: &Ray,
+        _hit: &HitRecord,
+        _direction: DVec3,
+    ) -> Option<(DVec3, f64)> {
+        None
+    }
+
+    // Returns true when the surface is cut out at the hit, objects then
+    // ignore it while intersecting rays.
+    fn is_cutout(
+        &self,
+        _hit: &HitRecord,
+    ) -> bool {
+        false
+    }
+
     fn emit(
         &self,
         _ray: &Ray,
@@ -24,4 +46,14 @@ pub trait Material: Debug {
     ) -> DVec3 {
         DVec3::ZERO
     }
+
+    // Called with the total area of the surfaces using the material,
+    // materials depending on it (e.g. lights given a power) return a copy
+    // of themselves adapted to that area.
+    fn for_surface_area(
+        &self,
+        _area: f64,
+    ) -> Option<Arc<dyn Material + Send + Sync>> {
+        None
+    }
 }
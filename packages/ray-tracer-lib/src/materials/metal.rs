@@ -2,7 +2,10 @@ use std::sync::Arc;
 
 use glam::DVec3;
 
-use rand::RngCore;
+use rand::{
+    Rng,
+    RngCore,
+};
 
 use crate::hitable::HitRecord;
 use crate::ray::Ray;
@@ -10,28 +13,80 @@ use crate::textures::{
     SolidColor,
     Texture,
 };
-use crate::vector::*;
 
 use super::material::Material;
+use super::microfacet::*;
+
+// Complex index of refraction eta + ik of a conductor, sampled at the red,
+// green and blue wavelengths.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Conductor {
+    pub eta: DVec3,
+    pub k: DVec3,
+}
+
+impl Conductor {
+    pub const ALUMINIUM: Self = Self {
+        eta: DVec3::new(1.657, 0.880, 0.521),
+        k: DVec3::new(9.224, 6.270, 4.837),
+    };
+
+    pub const COPPER: Self = Self {
+        eta: DVec3::new(0.200, 0.924, 1.102),
+        k: DVec3::new(3.912, 2.452, 2.142),
+    };
+
+    pub const GOLD: Self = Self {
+        eta: DVec3::new(0.143, 0.374, 1.442),
+        k: DVec3::new(3.983, 2.385, 1.603),
+    };
+
+    pub const SILVER: Self = Self {
+        eta: DVec3::new(0.155, 0.117, 0.138),
+        k: DVec3::new(4.828, 3.122, 2.147),
+    };
+}
 
 #[derive(Clone, Debug)]
 pub struct Metal {
-    fuzz: f64,
+    distribution: TrowbridgeReitz,
+    conductor: Option<Conductor>,
     texture: Arc<dyn Texture + Send + Sync>,
 }
 
 #[derive(Clone, Default)]
 pub struct MetalBuilder {
-    fuzz: Option<f64>,
+    roughness: Option<f64>,
+    anisotropy: Option<f64>,
+    conductor: Option<Conductor>,
     texture: Option<Arc<dyn Texture + Send + Sync>>,
 }
 
 impl MetalBuilder {
-    pub fn with_fuzz(
+    pub fn with_roughness(
+        &mut self,
+        value: Option<f64>,
+    ) -> &mut Self {
+        self.roughness = value;
+        self
+    }
+
+    pub fn with_anisotropy(
         &mut self,
         value: Option<f64>,
     ) -> &mut Self {
-        self.fuzz = value;
+        self.anisotropy = value;
+        self
+    }
+
+    // When a conductor is given its Fresnel reflectance is tinted by the
+    // texture color, otherwise the texture color is the reflectance at
+    // normal incidence.
+    pub fn with_conductor(
+        &mut self,
+        value: Option<Conductor>,
+    ) -> &mut Self {
+        self.conductor = value;
         self
     }
 
@@ -56,8 +111,14 @@ impl MetalBuilder {
     }
 
     pub fn build(self) -> Metal {
+        let (alpha_x, alpha_y) = roughness_to_alpha(
+            self.roughness.unwrap_or(0.0),
+            self.anisotropy.unwrap_or(0.0),
+        );
+
         Metal {
-            fuzz: self.fuzz.unwrap_or(0.0),
+            distribution: TrowbridgeReitz::new(alpha_x, alpha_y),
+            conductor: self.conductor,
             texture: self.texture.unwrap_or(Arc::new(SolidColor::default())),
         }
     }
@@ -69,6 +130,22 @@ impl Default for Metal {
     }
 }
 
+impl Metal {
+    fn fresnel(
+        &self,
+        hit: &HitRecord,
+        cos_theta: f64,
+    ) -> DVec3 {
+        let color = self.texture.get_filtered_color(hit.texture_coordinates, hit.point, hit.footprint);
+
+        if let Some(conductor) = self.conductor {
+            color*fresnel_conductor(cos_theta, conductor.eta, conductor.k)
+        } else {
+            fresnel_schlick(cos_theta, color)
+        }
+    }
+}
+
 impl Material for Metal {
     fn scatter(
         &self,
@@ -76,17 +153,64 @@ impl Material for Metal {
         hit: &HitRecord,
         rng: &mut dyn RngCore,
     ) -> Option<(Ray, DVec3)> {
-        let scatter_direction =
-            ray.get_direction().reflect(hit.normal).normalize()
-                + self.fuzz*random_in_unit_sphere(rng);
-
-        if scatter_direction.dot(hit.normal) > 0.0 {
-            Some((
-                Ray::new_at_time(hit.point, scatter_direction, ray.get_time()),
-                self.texture.get_color(hit.texture_coordinates, hit.point),
-            ))
+        let frame = Frame::from_hit(hit);
+        let wo = frame.to_local(-ray.get_direction().normalize());
+
+        if wo.z <= 0.0 {
+            return None;
+        }
+
+        let h = if self.distribution.is_smooth() {
+            DVec3::Z
         } else {
-            None
+            self.distribution.sample_visible_normal(wo, rng.random(), rng.random())
+        };
+
+        let wi = (2.0*wo.dot(h)*h - wo).normalize();
+
+        if wi.z <= 0.0 {
+            return None;
         }
+
+        let fresnel = self.fresnel(hit, wo.dot(h));
+        let attenuation = if self.distribution.is_smooth() {
+            fresnel
+        } else {
+            fresnel*self.distribution.g(wo, wi)/self.distribution.g1(wo)
+        };
+
+        Some((
+            Ray::new_at_time(hit.point, frame.to_world(wi), ray.get_time()),
+            attenuation,
+        ))
+    }
+
+    fn eval(
+        &self,
+        ray: &Ray,
+        hit: &HitRecord,
+        direction: DVec3,
+    ) -> Option<(DVec3, f64)> {
+        if self.distribution.is_smooth() {
+            return None;
+        }
+
+        let frame = Frame::from_hit(hit);
+        let wo = frame.to_local(-ray.get_direction().normalize());
+        let wi = frame.to_local(direction.normalize());
+
+        if wo.z <= 0.0 || wi.z <= 0.0 {
+            return Some((DVec3::ZERO, 0.0));
+        }
+
+        let h = (wo + wi).normalize();
+        let d = self.distribution.d(h);
+        let g = self.distribution.g(wo, wi);
+        let fresnel = self.fresnel(hit, wo.dot(h));
+
+        let value = fresnel*d*g/(4.0*wo.z);
+        let pdf = self.distribution.visible_normal_pdf(wo, h)/(4.0*wo.dot(h));
+
+        Some((value, pdf))
     }
 }
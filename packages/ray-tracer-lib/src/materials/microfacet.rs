@@ -0,0 +1,263 @@
+use std::f64::consts::PI;
+
+use glam::DVec3;
+
+use crate::hitable::HitRecord;
+
+// Below this alpha the distribution is treated as a perfect mirror.
+pub(crate) const MIN_ALPHA: f64 = 1e-3;
+
+// Orthonormal shading frame, the local z axis is the normal.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct Frame {
+    t: DVec3,
+    b: DVec3,
+    n: DVec3,
+}
+
+impl Frame {
+    pub(crate) fn from_normal(n: DVec3) -> Self {
+        let (t, b) = n.any_orthonormal_pair();
+
+        Self { t, b, n }
+    }
+
+    // The tangent of the hit orients anisotropic distributions.
+    pub(crate) fn from_hit(hit: &HitRecord) -> Self {
+        let n = hit.normal;
+
+        match (hit.tangent - n.dot(hit.tangent)*n).try_normalize() {
+            Some(t) => Self { t, b: n.cross(t), n },
+            None => Self::from_normal(n),
+        }
+    }
+
+    pub(crate) fn to_local(self, v: DVec3) -> DVec3 {
+        DVec3::new(v.dot(self.t), v.dot(self.b), v.dot(self.n))
+    }
+
+    pub(crate) fn to_world(self, v: DVec3) -> DVec3 {
+        v.x*self.t + v.y*self.b + v.z*self.n
+    }
+}
+
+// Maps a perceptual roughness and an anisotropy in [0, 1] to the alpha
+// parameters along the tangent and the bitangent.
+pub(crate) fn roughness_to_alpha(roughness: f64, anisotropy: f64) -> (f64, f64) {
+    let alpha = roughness.clamp(0.0, 1.0).powi(2);
+    let aspect = (1.0 - 0.9*anisotropy.clamp(0.0, 1.0)).sqrt();
+
+    (alpha/aspect, alpha*aspect)
+}
+
+// Trowbridge-Reitz (GGX) distribution of normals, all directions are given
+// in the local shading frame.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct TrowbridgeReitz {
+    alpha_x: f64,
+    alpha_y: f64,
+}
+
+impl TrowbridgeReitz {
+    pub(crate) fn new(alpha_x: f64, alpha_y: f64) -> Self {
+        Self {
+            alpha_x: alpha_x.max(MIN_ALPHA),
+            alpha_y: alpha_y.max(MIN_ALPHA),
+        }
+    }
+
+    pub(crate) fn is_smooth(&self) -> bool {
+        self.alpha_x.max(self.alpha_y) <= MIN_ALPHA
+    }
+
+    pub(crate) fn d(&self, h: DVec3) -> f64 {
+        if h.z <= 0.0 {
+            return 0.0;
+        }
+
+        let x = h.x/self.alpha_x;
+        let y = h.y/self.alpha_y;
+        let t = x*x + y*y + h.z*h.z;
+
+        1.0/(PI*self.alpha_x*self.alpha_y*t*t)
+    }
+
+    fn lambda(&self, w: DVec3) -> f64 {
+        if w.z == 0.0 {
+            return f64::INFINITY;
+        }
+
+        let x = self.alpha_x*w.x;
+        let y = self.alpha_y*w.y;
+
+        0.5*(-1.0 + (1.0 + (x*x + y*y)/(w.z*w.z)).sqrt())
+    }
+
+    pub(crate) fn g1(&self, w: DVec3) -> f64 {
+        1.0/(1.0 + self.lambda(w))
+    }
+
+    pub(crate) fn g(&self, wo: DVec3, wi: DVec3) -> f64 {
+        1.0/(1.0 + self.lambda(wo) + self.lambda(wi))
+    }
+
+    // Density of the visible normal h seen from wo.
+    pub(crate) fn visible_normal_pdf(&self, wo: DVec3, h: DVec3) -> f64 {
+        if wo.z <= 0.0 {
+            return 0.0;
+        }
+
+        self.g1(wo)*wo.dot(h).max(0.0)*self.d(h)/wo.z
+    }
+
+    // Samples a normal visible from wo, Heitz "Sampling the GGX Distribution
+    // of Visible Normals".
+    pub(crate) fn sample_visible_normal(&self, wo: DVec3, u1: f64, u2: f64) -> DVec3 {
+        let vh = DVec3::new(self.alpha_x*wo.x, self.alpha_y*wo.y, wo.z).normalize();
+
+        let len_sq = vh.x*vh.x + vh.y*vh.y;
+        let t1 = if len_sq > 0.0 {
+            DVec3::new(-vh.y, vh.x, 0.0)/len_sq.sqrt()
+        } else {
+            DVec3::X
+        };
+        let t2 = vh.cross(t1);
+
+        let r = u1.sqrt();
+        let phi = 2.0*PI*u2;
+        let p1 = r*phi.cos();
+        let p2 = r*phi.sin();
+        let s = 0.5*(1.0 + vh.z);
+        let p2 = (1.0 - s)*(1.0 - p1*p1).max(0.0).sqrt() + s*p2;
+
+        let nh = p1*t1 + p2*t2 + (1.0 - p1*p1 - p2*p2).max(0.0).sqrt()*vh;
+
+        DVec3::new(
+            self.alpha_x*nh.x,
+            self.alpha_y*nh.y,
+            nh.z.max(1e-6),
+        ).normalize()
+    }
+}
+
+pub(crate) fn fresnel_schlick(cos_theta: f64, f0: DVec3) -> DVec3 {
+    f0 + (DVec3::ONE - f0)*(1.0 - cos_theta.clamp(0.0, 1.0)).powi(5)
+}
+
+// Fresnel reflectance of a conductor of complex index of refraction
+// eta + ik, for unpolarized light.
+fn fresnel_conductor_channel(cos_theta: f64, eta: f64, k: f64) -> f64 {
+    let cos2 = cos_theta.clamp(0.0, 1.0).powi(2);
+    let sin2 = 1.0 - cos2;
+    let eta2 = eta*eta;
+    let k2 = k*k;
+
+    let t0 = eta2 - k2 - sin2;
+    let a2b2 = (t0*t0 + 4.0*eta2*k2).sqrt();
+    let t1 = a2b2 + cos2;
+    let a = (0.5*(a2b2 + t0)).max(0.0).sqrt();
+    let t2 = 2.0*cos_theta*a;
+    let rs = (t1 - t2)/(t1 + t2);
+
+    let t3 = cos2*a2b2 + sin2*sin2;
+    let t4 = t2*sin2;
+    let rp = rs*(t3 - t4)/(t3 + t4);
+
+    0.5*(rp + rs)
+}
+
+pub(crate) fn fresnel_conductor(cos_theta: f64, eta: DVec3, k: DVec3) -> DVec3 {
+    DVec3::new(
+        fresnel_conductor_channel(cos_theta, eta.x, k.x),
+        fresnel_conductor_channel(cos_theta, eta.y, k.y),
+        fresnel_conductor_channel(cos_theta, eta.z, k.z),
+    )
+}
+
+// Fresnel reflectance of a dielectric interface for unpolarized light, eta
+// being the ratio of the refraction index of the transmitted side over the
+// one of the incident side.
+pub(crate) fn fresnel_dielectric(cos_theta: f64, eta: f64) -> f64 {
+    let cos_i = cos_theta.clamp(0.0, 1.0);
+    let sin2_t = (1.0 - cos_i*cos_i)/(eta*eta);
+
+    if sin2_t >= 1.0 {
+        return 1.0;
+    }
+
+    let cos_t = (1.0 - sin2_t).sqrt();
+    let r_parallel = (eta*cos_i - cos_t)/(eta*cos_i + cos_t);
+    let r_perpendicular = (cos_i - eta*cos_t)/(cos_i + eta*cos_t);
+
+    0.5*(r_parallel*r_parallel + r_perpendicular*r_perpendicular)
+}
+
+// Rough dielectric BSDF times the cosine term and the density it is sampled
+// with, Walter et al. "Microfacet Models for Refraction through Rough
+// Surfaces". Directions are local to the frame of the incident side.
+pub(crate) fn eval_dielectric(
+    distribution: &TrowbridgeReitz,
+    wo: DVec3,
+    wi: DVec3,
+    eta: f64,
+) -> (DVec3, f64) {
+    if wo.z <= 0.0 || wi.z == 0.0 {
+        return (DVec3::ZERO, 0.0);
+    }
+
+    if wi.z > 0.0 {
+        let h = (wo + wi).normalize();
+        let fresnel = fresnel_dielectric(wo.dot(h), eta);
+
+        let value = fresnel*distribution.d(h)*distribution.g(wo, wi)/(4.0*wo.z);
+        let pdf = fresnel*distribution.visible_normal_pdf(wo, h)/(4.0*wo.dot(h));
+
+        (value*DVec3::ONE, pdf)
+    } else {
+        let h = (wo + eta*wi).normalize();
+        let h = if h.z < 0.0 { -h } else { h };
+
+        if wo.dot(h) <= 0.0 || wi.dot(h) >= 0.0 {
+            return (DVec3::ZERO, 0.0);
+        }
+
+        let transmittance = 1.0 - fresnel_dielectric(wo.dot(h), eta);
+        let denominator = (wi.dot(h) + wo.dot(h)/eta).powi(2);
+
+        let value = transmittance
+            *distribution.d(h)
+            *distribution.g(wo, wi)
+            *(wi.dot(h)*wo.dot(h)).abs()/(wo.z*denominator);
+        let pdf = transmittance
+            *distribution.visible_normal_pdf(wo, h)
+            *wi.dot(h).abs()/denominator;
+
+        (value*DVec3::ONE, pdf)
+    }
+}
+
+// Samples a direction reflected or refracted by a rough dielectric, u0
+// selects between the two and u1, u2 sample the visible normal.
+pub(crate) fn sample_dielectric(
+    distribution: &TrowbridgeReitz,
+    wo: DVec3,
+    eta: f64,
+    u0: f64,
+    u1: f64,
+    u2: f64,
+) -> Option<DVec3> {
+    let h = distribution.sample_visible_normal(wo, u1, u2);
+    let fresnel = fresnel_dielectric(wo.dot(h), eta);
+
+    let wi = if u0 < fresnel {
+        2.0*wo.dot(h)*h - wo
+    } else {
+        (-wo).refract(h, 1.0/eta)
+    };
+
+    if (u0 < fresnel && wi.z <= 0.0) || (u0 >= fresnel && wi.z >= 0.0) {
+        None
+    } else {
+        Some(wi.normalize())
+    }
+}
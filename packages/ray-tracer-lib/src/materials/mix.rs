@@ -0,0 +1,173 @@
+use std::sync::Arc;
+
+use glam::DVec3;
+
+use rand::{
+    Rng,
+    RngCore,
+};
+
+use crate::hitable::HitRecord;
+use crate::ray::Ray;
+use crate::textures::Texture;
+
+use super::lambertian::Lambertian;
+use super::material::Material;
+use super::parameter::Parameter;
+
+// Blend of two materials, the factor (or the luminance of the mask) being
+// the weight of the second one. Each hit scatters with one of them chosen
+// with a probability equal to its weight.
+#[derive(Clone, Debug)]
+pub struct Mix {
+    first: Arc<dyn Material + Send + Sync>,
+    second: Arc<dyn Material + Send + Sync>,
+    factor: Parameter,
+}
+
+#[derive(Clone, Default)]
+pub struct MixBuilder {
+    first: Option<Arc<dyn Material + Send + Sync>>,
+    second: Option<Arc<dyn Material + Send + Sync>>,
+    factor: Option<f64>,
+    mask: Option<Arc<dyn Texture + Send + Sync>>,
+}
+
+impl MixBuilder {
+    pub fn with_first(
+        &mut self,
+        value: Option<Arc<dyn Material + Send + Sync>>,
+    ) -> &mut Self {
+        self.first = value;
+        self
+    }
+
+    pub fn with_second(
+        &mut self,
+        value: Option<Arc<dyn Material + Send + Sync>>,
+    ) -> &mut Self {
+        self.second = value;
+        self
+    }
+
+    pub fn with_factor(
+        &mut self,
+        value: Option<f64>,
+    ) -> &mut Self {
+        self.factor = value;
+        self
+    }
+
+    // Overrides the factor.
+    pub fn with_mask(
+        &mut self,
+        value: Option<Arc<dyn Texture + Send + Sync>>,
+    ) -> &mut Self {
+        self.mask = value;
+        self
+    }
+
+    pub fn build(self) -> Mix {
+        Mix {
+            first: self.first.unwrap_or(Arc::new(Lambertian::default())),
+            second: self.second.unwrap_or(Arc::new(Lambertian::default())),
+            factor: Parameter::new(self.factor, self.mask, 0.5),
+        }
+    }
+}
+
+impl Mix {
+    fn get_factor(&self, hit: &HitRecord) -> f64 {
+        self.factor.get(hit).clamp(0.0, 1.0)
+    }
+}
+
+impl Material for Mix {
+    fn scatter(
+        &self,
+        ray: &Ray,
+        hit: &HitRecord,
+        rng: &mut dyn RngCore
+    ) -> Option<(Ray, DVec3)> {
+        let material = if rng.random::<f64>() < self.get_factor(hit) {
+            &self.second
+        } else {
+            &self.first
+        };
+
+        let (mut scattered_ray, color) = material.scatter(ray, hit, rng)?;
+
+        // The mixed density does not account for a direction sampled by a
+        // specular lobe.
+        if material.eval(ray, hit, scattered_ray.get_direction()).is_none() {
+            scattered_ray.specular();
+        }
+
+        Some((scattered_ray, color))
+    }
+
+    fn eval(
+        &self,
+        ray: &Ray,
+        hit: &HitRecord,
+        direction: DVec3,
+    ) -> Option<(DVec3, f64)> {
+        let factor = self.get_factor(hit);
+
+        let first = self.first.eval(ray, hit, direction);
+        let second = self.second.eval(ray, hit, direction);
+
+        if first.is_none() && second.is_none() {
+            return None;
+        }
+
+        let (first_value, first_pdf) = first.unwrap_or((DVec3::ZERO, 0.0));
+        let (second_value, second_pdf) = second.unwrap_or((DVec3::ZERO, 0.0));
+
+        Some((
+            (1.0 - factor)*first_value + factor*second_value,
+            (1.0 - factor)*first_pdf + factor*second_pdf,
+        ))
+    }
+
+    // Intersections cannot be chosen randomly, the surface is cut out
+    // wherever the material with the largest weight is.
+    fn is_cutout(
+        &self,
+        hit: &HitRecord,
+    ) -> bool {
+        if self.get_factor(hit) < 0.5 {
+            self.first.is_cutout(hit)
+        } else {
+            self.second.is_cutout(hit)
+        }
+    }
+
+    fn emit(
+        &self,
+        ray: &Ray,
+        hit: &HitRecord,
+    ) -> DVec3 {
+        let factor = self.get_factor(hit);
+
+        (1.0 - factor)*self.first.emit(ray, hit) + factor*self.second.emit(ray, hit)
+    }
+
+    fn for_surface_area(
+        &self,
+        area: f64,
+    ) -> Option<Arc<dyn Material + Send + Sync>> {
+        let first = self.first.for_surface_area(area);
+        let second = self.second.for_surface_area(area);
+
+        if first.is_none() && second.is_none() {
+            return None;
+        }
+
+        Some(Arc::new(Mix {
+            first: first.unwrap_or(self.first.clone()),
+            second: second.unwrap_or(self.second.clone()),
+            factor: self.factor.clone(),
+        }))
+    }
+}
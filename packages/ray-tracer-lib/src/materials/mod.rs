@@ -1,13 +1,31 @@
 mod material;
 
+mod bump_map;
+mod cutout;
 mod diffuse_light;
 mod dielectric;
 mod lambertian;
 mod metal;
+mod microfacet;
+mod mix;
+mod normal_map;
+mod parameter;
+mod principled;
+mod subsurface;
+mod thin_dielectric;
+mod translucent;
 
 pub use material::*;
 
+pub use bump_map::*;
+pub use cutout::*;
 pub use diffuse_light::*;
 pub use dielectric::*;
 pub use lambertian::*;
 pub use metal::*;
+pub use mix::*;
+pub use normal_map::*;
+pub use principled::*;
+pub use subsurface::*;
+pub use thin_dielectric::*;
+pub use translucent::*;
@@ -0,0 +1,147 @@
+use std::sync::Arc;
+
+use glam::DVec3;
+
+use rand::RngCore;
+
+use crate::hitable::HitRecord;
+use crate::ray::Ray;
+use crate::textures::{
+    SolidColor,
+    Texture,
+};
+
+use super::lambertian::Lambertian;
+use super::material::Material;
+
+// Returns a copy of the hit whose normal is replaced by the given one,
+// expressed in the tangent frame of the outward side of the surface.
+pub(crate) fn perturb_normal(hit: &HitRecord, local_normal: DVec3) -> HitRecord {
+    let n = if hit.front_face { hit.normal } else { -hit.normal };
+    let t = (hit.tangent - n.dot(hit.tangent)*n)
+        .try_normalize()
+        .unwrap_or_else(|| n.any_orthonormal_vector());
+    let b = n.cross(t);
+
+    let mut hit = hit.clone();
+
+    // Perturbations bending the normal past the surface are ignored.
+    if let Some(normal) = (local_normal.x*t + local_normal.y*b + local_normal.z*n)
+        .try_normalize()
+        .filter(|normal| normal.dot(n) > 0.0)
+    {
+        hit.normal = if hit.front_face { normal } else { -normal };
+    }
+
+    hit
+}
+
+// Replaces the normal of the underlying material by the one read from a
+// tangent space normal map, colors in [0, 1] mapping to [-1, 1]. Image maps
+// must be decoded as linear.
+#[derive(Clone, Debug)]
+pub struct NormalMap {
+    material: Arc<dyn Material + Send + Sync>,
+    texture: Arc<dyn Texture + Send + Sync>,
+    strength: f64,
+}
+
+#[derive(Clone, Default)]
+pub struct NormalMapBuilder {
+    material: Option<Arc<dyn Material + Send + Sync>>,
+    texture: Option<Arc<dyn Texture + Send + Sync>>,
+    strength: Option<f64>,
+}
+
+impl NormalMapBuilder {
+    pub fn with_material(
+        &mut self,
+        value: Option<Arc<dyn Material + Send + Sync>>,
+    ) -> &mut Self {
+        self.material = value;
+        self
+    }
+
+    pub fn with_texture(
+        &mut self,
+        value: Option<Arc<dyn Texture + Send + Sync>>,
+    ) -> &mut Self {
+        self.texture = value;
+        self
+    }
+
+    // Scales the tangential components of the normal.
+    pub fn with_strength(
+        &mut self,
+        value: Option<f64>,
+    ) -> &mut Self {
+        self.strength = value;
+        self
+    }
+
+    pub fn build(self) -> NormalMap {
+        NormalMap {
+            material: self.material.unwrap_or(Arc::new(Lambertian::default())),
+            texture: self.texture.unwrap_or(Arc::new(SolidColor::new(DVec3::new(0.5, 0.5, 1.0)))),
+            strength: self.strength.unwrap_or(1.0),
+        }
+    }
+}
+
+impl NormalMap {
+    fn get_hit(&self, hit: &HitRecord) -> HitRecord {
+        let color = self.texture.get_filtered_color(hit.texture_coordinates, hit.point, hit.footprint);
+        let normal = 2.0*color - DVec3::ONE;
+
+        perturb_normal(hit, DVec3::new(
+            self.strength*normal.x,
+            self.strength*normal.y,
+            normal.z,
+        ))
+    }
+}
+
+impl Material for NormalMap {
+    fn scatter(
+        &self,
+        ray: &Ray,
+        hit: &HitRecord,
+        rng: &mut dyn RngCore,
+    ) -> Option<(Ray, DVec3)> {
+        self.material.scatter(ray, &self.get_hit(hit), rng)
+    }
+
+    fn eval(
+        &self,
+        ray: &Ray,
+        hit: &HitRecord,
+        direction: DVec3,
+    ) -> Option<(DVec3, f64)> {
+        self.material.eval(ray, &self.get_hit(hit), direction)
+    }
+
+    fn is_cutout(
+        &self,
+        hit: &HitRecord,
+    ) -> bool {
+        self.material.is_cutout(hit)
+    }
+
+    fn emit(
+        &self,
+        ray: &Ray,
+        hit: &HitRecord,
+    ) -> DVec3 {
+        self.material.emit(ray, &self.get_hit(hit))
+    }
+
+    fn for_surface_area(
+        &self,
+        area: f64,
+    ) -> Option<Arc<dyn Material + Send + Sync>> {
+        Some(Arc::new(NormalMap {
+            material: self.material.for_surface_area(area)?,
+            ..self.clone()
+        }))
+    }
+}
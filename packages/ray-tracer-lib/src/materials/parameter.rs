@@ -0,0 +1,36 @@
+use std::sync::Arc;
+
+use crate::hitable::HitRecord;
+use crate::spectrum::luminance;
+use crate::textures::Texture;
+
+// Scalar material parameter, either constant or read from the luminance of
+// a texture at the hit.
+#[derive(Clone, Debug)]
+pub(crate) enum Parameter {
+    Value(f64),
+    Texture(Arc<dyn Texture + Send + Sync>),
+}
+
+impl Parameter {
+    pub(crate) fn new(
+        value: Option<f64>,
+        texture: Option<Arc<dyn Texture + Send + Sync>>,
+        default: f64,
+    ) -> Self {
+        if let Some(texture) = texture {
+            Self::Texture(texture)
+        } else {
+            Self::Value(value.unwrap_or(default))
+        }
+    }
+
+    pub(crate) fn get(&self, hit: &HitRecord) -> f64 {
+        match self {
+            Self::Value(value) => *value,
+            Self::Texture(texture) => {
+                luminance(texture.get_filtered_color(hit.texture_coordinates, hit.point, hit.footprint))
+            },
+        }
+    }
+}
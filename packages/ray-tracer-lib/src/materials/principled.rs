@@ -0,0 +1,510 @@
+use std::f64::consts::PI;
+use std::sync::Arc;
+
+use glam::DVec3;
+
+use rand::{
+    Rng,
+    RngCore,
+};
+
+use crate::hitable::HitRecord;
+use crate::ray::Ray;
+use crate::spectrum::luminance;
+use crate::textures::{
+    SolidColor,
+    Texture,
+};
+use crate::vector::*;
+
+use super::material::Material;
+use super::microfacet::*;
+use super::parameter::Parameter;
+
+fn reflectance(cosine: f64, refraction_index: f64) -> f64 {
+    let r0 = ((1.0 - refraction_index)/(1.0 + refraction_index)).powi(2);
+
+    r0 + (1.0 - r0)*(1.0 - cosine).powi(5)
+}
+
+#[derive(Clone, Debug)]
+pub struct Principled {
+    base_color: Arc<dyn Texture + Send + Sync>,
+    metallic: Parameter,
+    roughness: Parameter,
+    specular: Parameter,
+    clearcoat: Parameter,
+    clearcoat_roughness: f64,
+    sheen: Parameter,
+    transmission: Parameter,
+    refraction_index: f64,
+}
+
+#[derive(Clone, Default)]
+pub struct PrincipledBuilder {
+    base_color: Option<Arc<dyn Texture + Send + Sync>>,
+    metallic: Option<f64>,
+    metallic_texture: Option<Arc<dyn Texture + Send + Sync>>,
+    roughness: Option<f64>,
+    roughness_texture: Option<Arc<dyn Texture + Send + Sync>>,
+    specular: Option<f64>,
+    specular_texture: Option<Arc<dyn Texture + Send + Sync>>,
+    clearcoat: Option<f64>,
+    clearcoat_texture: Option<Arc<dyn Texture + Send + Sync>>,
+    clearcoat_roughness: Option<f64>,
+    sheen: Option<f64>,
+    sheen_texture: Option<Arc<dyn Texture + Send + Sync>>,
+    transmission: Option<f64>,
+    transmission_texture: Option<Arc<dyn Texture + Send + Sync>>,
+    refraction_index: Option<f64>,
+}
+
+impl PrincipledBuilder {
+    pub fn with_base_color(
+        &mut self,
+        color: Option<DVec3>,
+    ) -> &mut Self {
+        if let Some(color) = color {
+            self.base_color.replace(Arc::new(SolidColor::new(color)));
+        }
+        self
+    }
+
+    pub fn with_base_color_texture(
+        &mut self,
+        texture: Option<Arc<dyn Texture + Send + Sync>>,
+    ) -> &mut Self {
+        if let Some(texture) = texture {
+            self.base_color.replace(texture);
+        }
+        self
+    }
+
+    pub fn with_metallic(
+        &mut self,
+        value: Option<f64>,
+    ) -> &mut Self {
+        self.metallic = value;
+        self
+    }
+
+    pub fn with_metallic_texture(
+        &mut self,
+        texture: Option<Arc<dyn Texture + Send + Sync>>,
+    ) -> &mut Self {
+        self.metallic_texture = texture;
+        self
+    }
+
+    pub fn with_roughness(
+        &mut self,
+        value: Option<f64>,
+    ) -> &mut Self {
+        self.roughness = value;
+        self
+    }
+
+    pub fn with_roughness_texture(
+        &mut self,
+        texture: Option<Arc<dyn Texture + Send + Sync>>,
+    ) -> &mut Self {
+        self.roughness_texture = texture;
+        self
+    }
+
+    pub fn with_specular(
+        &mut self,
+        value: Option<f64>,
+    ) -> &mut Self {
+        self.specular = value;
+        self
+    }
+
+    pub fn with_specular_texture(
+        &mut self,
+        texture: Option<Arc<dyn Texture + Send + Sync>>,
+    ) -> &mut Self {
+        self.specular_texture = texture;
+        self
+    }
+
+    pub fn with_clearcoat(
+        &mut self,
+        value: Option<f64>,
+    ) -> &mut Self {
+        self.clearcoat = value;
+        self
+    }
+
+    pub fn with_clearcoat_texture(
+        &mut self,
+        texture: Option<Arc<dyn Texture + Send + Sync>>,
+    ) -> &mut Self {
+        self.clearcoat_texture = texture;
+        self
+    }
+
+    pub fn with_clearcoat_roughness(
+        &mut self,
+        value: Option<f64>,
+    ) -> &mut Self {
+        self.clearcoat_roughness = value;
+        self
+    }
+
+    pub fn with_sheen(
+        &mut self,
+        value: Option<f64>,
+    ) -> &mut Self {
+        self.sheen = value;
+        self
+    }
+
+    pub fn with_sheen_texture(
+        &mut self,
+        texture: Option<Arc<dyn Texture + Send + Sync>>,
+    ) -> &mut Self {
+        self.sheen_texture = texture;
+        self
+    }
+
+    pub fn with_transmission(
+        &mut self,
+        value: Option<f64>,
+    ) -> &mut Self {
+        self.transmission = value;
+        self
+    }
+
+    pub fn with_transmission_texture(
+        &mut self,
+        texture: Option<Arc<dyn Texture + Send + Sync>>,
+    ) -> &mut Self {
+        self.transmission_texture = texture;
+        self
+    }
+
+    pub fn with_refraction_index(
+        &mut self,
+        value: Option<f64>,
+    ) -> &mut Self {
+        self.refraction_index = value;
+        self
+    }
+
+    pub fn build(self) -> Principled {
+        Principled {
+            base_color: self.base_color.unwrap_or(Arc::new(SolidColor::new(0.8*DVec3::ONE))),
+            metallic: Parameter::new(self.metallic, self.metallic_texture, 0.0),
+            roughness: Parameter::new(self.roughness, self.roughness_texture, 0.5),
+            specular: Parameter::new(self.specular, self.specular_texture, 0.5),
+            clearcoat: Parameter::new(self.clearcoat, self.clearcoat_texture, 0.0),
+            clearcoat_roughness: self.clearcoat_roughness.unwrap_or(0.03),
+            sheen: Parameter::new(self.sheen, self.sheen_texture, 0.0),
+            transmission: Parameter::new(self.transmission, self.transmission_texture, 0.0),
+            refraction_index: self.refraction_index.unwrap_or(1.5),
+        }
+    }
+}
+
+impl Default for Principled {
+    fn default() -> Self {
+        PrincipledBuilder::default().build()
+    }
+}
+
+// Lobes of the material at a given hit point, with the probabilities they
+// are sampled with.
+struct Lobes {
+    frame: Frame,
+    base_color: DVec3,
+    diffuse: f64,
+    sheen: f64,
+    specular: f64,
+    specular_f0: DVec3,
+    specular_distribution: TrowbridgeReitz,
+    clearcoat: f64,
+    clearcoat_distribution: TrowbridgeReitz,
+    transmission: f64,
+    // Ratio of the refraction indices of the transmitted and incident sides.
+    eta: f64,
+    // Sampling probabilities of the diffuse, specular, clearcoat and
+    // transmission lobes.
+    probabilities: [f64; 4],
+}
+
+impl Principled {
+    fn get_lobes(
+        &self,
+        wo: DVec3,
+        hit: &HitRecord,
+    ) -> Lobes {
+        let base_color = self.base_color.get_filtered_color(hit.texture_coordinates, hit.point, hit.footprint);
+        let metallic = self.metallic.get(hit).clamp(0.0, 1.0);
+        let roughness = self.roughness.get(hit).clamp(0.0, 1.0);
+        let specular = self.specular.get(hit).max(0.0);
+        let clearcoat = self.clearcoat.get(hit).clamp(0.0, 1.0);
+        let sheen = self.sheen.get(hit).max(0.0);
+        let transmission = self.transmission.get(hit).clamp(0.0, 1.0);
+
+        let diffuse_weight = (1.0 - metallic)*(1.0 - transmission);
+        let transmission_weight = (1.0 - metallic)*transmission;
+        let specular_weight = 1.0 - transmission_weight;
+        let clearcoat_weight = 0.25*clearcoat;
+
+        let specular_f0 = (0.08*specular*DVec3::ONE).lerp(base_color, metallic);
+
+        let (alpha_x, alpha_y) = roughness_to_alpha(roughness, 0.0);
+        let (clearcoat_alpha, _) = roughness_to_alpha(self.clearcoat_roughness, 0.0);
+
+        let probabilities = [
+            diffuse_weight*luminance(base_color).max(0.05),
+            specular_weight*luminance(fresnel_schlick(wo.z, specular_f0)).max(0.05),
+            clearcoat_weight*reflectance(wo.z, 1.5),
+            transmission_weight,
+        ];
+        let total = probabilities.iter().sum::<f64>();
+        let probabilities = probabilities.map(|p| if total > 0.0 { p/total } else { 0.0 });
+
+        Lobes {
+            frame: Frame::from_hit(hit),
+            base_color,
+            diffuse: diffuse_weight,
+            sheen: sheen*(1.0 - metallic),
+            specular: specular_weight,
+            specular_f0,
+            specular_distribution: TrowbridgeReitz::new(alpha_x, alpha_y),
+            clearcoat: clearcoat_weight,
+            clearcoat_distribution: TrowbridgeReitz::new(clearcoat_alpha, clearcoat_alpha),
+            transmission: transmission_weight,
+            eta: if hit.front_face { self.refraction_index } else { 1.0/self.refraction_index },
+            probabilities,
+        }
+    }
+
+    fn eval_microfacet(
+        distribution: &TrowbridgeReitz,
+        f0: DVec3,
+        wo: DVec3,
+        wi: DVec3,
+    ) -> (DVec3, f64) {
+        let h = (wo + wi).normalize();
+
+        if distribution.is_smooth() {
+            return (DVec3::ZERO, 0.0);
+        }
+
+        let value = fresnel_schlick(wo.dot(h), f0)
+            *distribution.d(h)
+            *distribution.g(wo, wi)/(4.0*wo.z);
+        let pdf = distribution.visible_normal_pdf(wo, h)/(4.0*wo.dot(h));
+
+        (value, pdf)
+    }
+
+    // Non specular part of the BSDF times the cosine term, and the density
+    // of the mixture of the non specular lobes.
+    fn eval_lobes(
+        &self,
+        lobes: &Lobes,
+        wo: DVec3,
+        wi: DVec3,
+    ) -> (DVec3, f64) {
+        if wo.z <= 0.0 {
+            return (DVec3::ZERO, 0.0);
+        }
+
+        let [
+            diffuse_probability,
+            specular_probability,
+            clearcoat_probability,
+            transmission_probability,
+        ] = lobes.probabilities;
+
+        // A rough transmission lobe can be evaluated, refracted light is
+        // tinted by the base color.
+        let (transmission, transmission_pdf) =
+            if lobes.transmission > 0.0 && !lobes.specular_distribution.is_smooth() {
+                let (value, pdf) = eval_dielectric(&lobes.specular_distribution, wo, wi, lobes.eta);
+
+                if wi.z < 0.0 {
+                    (lobes.transmission*lobes.base_color*value, transmission_probability*pdf)
+                } else {
+                    (lobes.transmission*value, transmission_probability*pdf)
+                }
+            } else {
+                (DVec3::ZERO, 0.0)
+            };
+
+        if wi.z <= 0.0 {
+            return (transmission, transmission_pdf);
+        }
+
+        let h = (wo + wi).normalize();
+        let sheen = lobes.sheen*(1.0 - wi.dot(h).clamp(0.0, 1.0)).powi(5);
+
+        let diffuse = lobes.diffuse*lobes.base_color*wi.z/PI + sheen*wi.z*DVec3::ONE;
+        let diffuse_pdf = wi.z/PI;
+
+        let (specular, specular_pdf) = Self::eval_microfacet(
+            &lobes.specular_distribution,
+            lobes.specular_f0,
+            wo,
+            wi,
+        );
+
+        let (clearcoat, clearcoat_pdf) = Self::eval_microfacet(
+            &lobes.clearcoat_distribution,
+            0.04*DVec3::ONE,
+            wo,
+            wi,
+        );
+
+        let value = diffuse + lobes.specular*specular + lobes.clearcoat*clearcoat + transmission;
+        let pdf =
+            diffuse_probability*diffuse_pdf
+            + specular_probability*specular_pdf
+            + clearcoat_probability*clearcoat_pdf
+            + transmission_pdf;
+
+        (value, pdf)
+    }
+
+    fn scatter_transmission(
+        &self,
+        ray: &Ray,
+        hit: &HitRecord,
+        lobes: &Lobes,
+        rng: &mut dyn RngCore,
+    ) -> Option<(Ray, DVec3)> {
+        let ri = 1.0/lobes.eta;
+
+        let unit_direction = ray.get_direction().normalize();
+
+        let cos_theta = (-unit_direction).dot(hit.normal).min(1.0);
+        let sin_theta = (1.0 - cos_theta*cos_theta).sqrt();
+
+        let (direction, color) =
+            if ri*sin_theta > 1.0 || reflectance(cos_theta, ri) > rng.random::<f64>() {
+                (unit_direction.reflect(hit.normal), DVec3::ONE)
+            } else {
+                (unit_direction.refract(hit.normal, ri), lobes.base_color)
+            };
+
+        let mut scattered_ray = Ray::new_at_time(hit.point, direction, ray.get_time());
+
+        scattered_ray.specular();
+
+        Some((scattered_ray, color*lobes.transmission/lobes.probabilities[3]))
+    }
+}
+
+impl Material for Principled {
+    fn scatter(
+        &self,
+        ray: &Ray,
+        hit: &HitRecord,
+        rng: &mut dyn RngCore,
+    ) -> Option<(Ray, DVec3)> {
+        let wo = Frame::from_hit(hit).to_local(-ray.get_direction().normalize());
+        let lobes = self.get_lobes(wo, hit);
+
+        let [diffuse_probability, specular_probability, clearcoat_probability, _] = lobes.probabilities;
+
+        let u = rng.random::<f64>();
+
+        let wi = if u < diffuse_probability {
+            let direction = DVec3::Z + random_unit_vector(rng);
+
+            if direction.almost_zero(1e-8) {
+                DVec3::Z
+            } else {
+                direction.normalize()
+            }
+        } else if u < diffuse_probability + specular_probability {
+            let h = if lobes.specular_distribution.is_smooth() {
+                DVec3::Z
+            } else {
+                lobes.specular_distribution.sample_visible_normal(wo, rng.random(), rng.random())
+            };
+
+            2.0*wo.dot(h)*h - wo
+        } else if u < diffuse_probability + specular_probability + clearcoat_probability {
+            let h = if lobes.clearcoat_distribution.is_smooth() {
+                DVec3::Z
+            } else {
+                lobes.clearcoat_distribution.sample_visible_normal(wo, rng.random(), rng.random())
+            };
+
+            2.0*wo.dot(h)*h - wo
+        } else if lobes.specular_distribution.is_smooth() {
+            return self.scatter_transmission(ray, hit, &lobes, rng);
+        } else {
+            sample_dielectric(
+                &lobes.specular_distribution,
+                wo,
+                lobes.eta,
+                rng.random(),
+                rng.random(),
+                rng.random(),
+            )?
+        };
+
+        let transmitted = u >= diffuse_probability + specular_probability + clearcoat_probability;
+
+        if wi.z <= 0.0 && !transmitted {
+            return None;
+        }
+
+        let direction = lobes.frame.to_world(wi);
+
+        // Perfectly smooth specular and clearcoat lobes cannot be evaluated,
+        // their samples are weighted on their own.
+        if u >= diffuse_probability
+            && u < diffuse_probability + specular_probability
+            && lobes.specular_distribution.is_smooth() {
+            let mut scattered_ray = Ray::new_at_time(hit.point, direction, ray.get_time());
+            let value = lobes.specular*fresnel_schlick(wo.z, lobes.specular_f0);
+
+            scattered_ray.specular();
+
+            return Some((scattered_ray, value/specular_probability));
+        }
+
+        if u >= diffuse_probability + specular_probability
+            && u < diffuse_probability + specular_probability + clearcoat_probability
+            && lobes.clearcoat_distribution.is_smooth() {
+            let mut scattered_ray = Ray::new_at_time(hit.point, direction, ray.get_time());
+            let value = lobes.clearcoat*fresnel_schlick(wo.z, 0.04*DVec3::ONE);
+
+            scattered_ray.specular();
+
+            return Some((scattered_ray, value/clearcoat_probability));
+        }
+
+        let (value, pdf) = self.eval_lobes(&lobes, wo, wi);
+
+        if pdf <= 0.0 {
+            return None;
+        }
+
+        Some((
+            Ray::new_at_time(hit.point, direction, ray.get_time()),
+            value/pdf,
+        ))
+    }
+
+    fn eval(
+        &self,
+        ray: &Ray,
+        hit: &HitRecord,
+        direction: DVec3,
+    ) -> Option<(DVec3, f64)> {
+        let frame = Frame::from_hit(hit);
+        let wo = frame.to_local(-ray.get_direction().normalize());
+        let wi = frame.to_local(direction.normalize());
+        let lobes = self.get_lobes(wo, hit);
+
+        Some(self.eval_lobes(&lobes, wo, wi))
+    }
+}
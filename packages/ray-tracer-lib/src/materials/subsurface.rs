@@ -0,0 +1,272 @@
+use std::f64::consts::PI;
+use std::sync::Arc;
+
+use glam::DVec3;
+
+use rand::{
+    Rng,
+    RngCore,
+};
+
+use crate::hitable::HitRecord;
+use crate::ray::Ray;
+use crate::textures::{
+    SolidColor,
+    Texture,
+};
+use crate::vector::*;
+
+use super::material::Material;
+
+fn reflectance(cosine: f64, refraction_index: f64) -> f64 {
+    let r0 = ((1.0 - refraction_index)/(1.0 + refraction_index)).powi(2);
+
+    r0 + (1.0 - r0)*(1.0 - cosine).powi(5)
+}
+
+// Single scattering albedo of a medium whose multiple scattering albedo is
+// the given one, Chiang et al. "Practical and Controllable Subsurface
+// Scattering for Production Path Tracing".
+fn single_scattering_albedo(albedo: DVec3) -> DVec3 {
+    albedo.clamp(DVec3::ZERO, DVec3::splat(0.999)).map(|a| {
+        let s = 4.09712 + 4.20863*a - (9.59217 + 41.6808*a + 17.7126*a*a).sqrt();
+
+        1.0 - s*s
+    })
+}
+
+// Cosine weighted direction around the given normal.
+fn diffuse_direction(normal: DVec3, rng: &mut dyn RngCore) -> DVec3 {
+    let direction = normal + random_unit_vector(rng);
+
+    if direction.almost_zero(1e-8) {
+        normal
+    } else {
+        direction
+    }
+}
+
+// Random walk subsurface scattering through a homogeneous medium enclosed by
+// the surface. Rays enter the medium diffusely, then each hit of the surface
+// from inside either scatters isotropically somewhere along the traveled
+// segment or reaches the boundary, where the ray is reflected back inside
+// with the Fresnel reflectance or leaves. Every step of the walk is a
+// bounce, dense media need a higher bounce count.
+#[derive(Clone, Debug)]
+pub struct Subsurface {
+    texture: Arc<dyn Texture + Send + Sync>,
+    // Extinction coefficient, inverse of the mean free path.
+    extinction: DVec3,
+    refraction_index: f64,
+}
+
+#[derive(Clone, Default)]
+pub struct SubsurfaceBuilder {
+    texture: Option<Arc<dyn Texture + Send + Sync>>,
+    mean_free_path: Option<DVec3>,
+    refraction_index: Option<f64>,
+}
+
+impl SubsurfaceBuilder {
+    // Color of the surface, the multiple scattering albedo of the medium.
+    pub fn with_albedo(
+        &mut self,
+        color: Option<DVec3>,
+    ) -> &mut Self {
+        self.texture = if let Some(color) = color {
+            Some(Arc::new(SolidColor::new(color)))
+        } else {
+            None
+        };
+        self
+    }
+
+    pub fn with_texture(
+        &mut self,
+        texture: Option<Arc<dyn Texture + Send + Sync>>,
+    ) -> &mut Self {
+        self.texture = texture;
+        self
+    }
+
+    // Average distance traveled in the medium between two scattering events
+    // for each channel.
+    pub fn with_mean_free_path(
+        &mut self,
+        value: Option<DVec3>,
+    ) -> &mut Self {
+        self.mean_free_path = value;
+        self
+    }
+
+    pub fn with_refraction_index(
+        &mut self,
+        value: Option<f64>,
+    ) -> &mut Self {
+        self.refraction_index = value;
+        self
+    }
+
+    pub fn build(self) -> Subsurface {
+        let mean_free_path = self.mean_free_path
+            .unwrap_or(DVec3::splat(0.1))
+            .max(DVec3::splat(1e-6));
+
+        Subsurface {
+            texture: self.texture.unwrap_or(Arc::new(SolidColor::new(0.8*DVec3::ONE))),
+            extinction: 1.0/mean_free_path,
+            refraction_index: self.refraction_index.unwrap_or(1.5),
+        }
+    }
+}
+
+impl Default for Subsurface {
+    fn default() -> Self {
+        SubsurfaceBuilder::default().build()
+    }
+}
+
+impl Subsurface {
+    // The medium takes the color of the surface where the walk reaches it.
+    fn get_albedo(&self, hit: &HitRecord) -> DVec3 {
+        single_scattering_albedo(self.texture.get_filtered_color(
+            hit.texture_coordinates,
+            hit.point,
+            hit.footprint,
+        ))
+    }
+
+    // Transmittance along the segment traveled inside the medium up to the
+    // hit and the probability the walk samples no scattering event on it.
+    fn get_transmittance(
+        &self,
+        ray: &Ray,
+        hit: &HitRecord,
+    ) -> (DVec3, f64) {
+        let distance = hit.t*ray.get_direction().length();
+        let transmittance = (-self.extinction*distance).exp();
+
+        (transmittance, transmittance.element_sum()/3.0)
+    }
+
+    // Reflectance of the boundary for a ray leaving the medium, rays beyond
+    // the critical angle are totally reflected.
+    fn get_exit_reflectance(
+        &self,
+        ray: &Ray,
+        hit: &HitRecord,
+    ) -> f64 {
+        let cos_theta = (-ray.get_direction().normalize()).dot(hit.normal).min(1.0);
+        let sin_theta = (1.0 - cos_theta*cos_theta).sqrt();
+
+        if self.refraction_index*sin_theta > 1.0 {
+            1.0
+        } else {
+            reflectance(cos_theta, self.refraction_index)
+        }
+    }
+}
+
+impl Material for Subsurface {
+    fn scatter(
+        &self,
+        ray: &Ray,
+        hit: &HitRecord,
+        rng: &mut dyn RngCore,
+    ) -> Option<(Ray, DVec3)> {
+        let unit_direction = ray.get_direction().normalize();
+
+        // The normal always faces the incoming ray, -normal points inside
+        // the medium when entering it and outside when leaving it.
+        if hit.front_face {
+            let cos_theta = (-unit_direction).dot(hit.normal).min(1.0);
+
+            if reflectance(cos_theta, self.refraction_index) > rng.random_range(0.0..1.0) {
+                let mut scattered_ray = Ray::new_at_time(
+                    hit.point,
+                    unit_direction.reflect(hit.normal),
+                    ray.get_time(),
+                );
+
+                scattered_ray.specular();
+                return Some((scattered_ray, DVec3::ONE));
+            }
+
+            return Some((
+                Ray::new_at_time(hit.point, diffuse_direction(-hit.normal, rng), ray.get_time()),
+                DVec3::ONE,
+            ));
+        }
+
+        // Distances are sampled for a channel picked at random, weighted by
+        // the average density over all channels.
+        let distance = hit.t*ray.get_direction().length();
+        let channel = rng.random_range(0..3);
+        let s = -(1.0 - rng.random_range(0.0..1.0f64)).ln()/self.extinction[channel];
+
+        if s < distance {
+            let transmittance = (-self.extinction*s).exp();
+            let pdf = (self.extinction*transmittance).element_sum()/3.0;
+            let point = ray.get_origin() + s*unit_direction;
+
+            let mut scattered_ray = Ray::new_at_time(
+                point,
+                random_unit_vector(rng),
+                ray.get_time(),
+            );
+
+            // Lights are not sampled from inside the medium.
+            scattered_ray.specular();
+
+            return Some((
+                scattered_ray,
+                self.get_albedo(hit)*self.extinction*transmittance/pdf,
+            ));
+        }
+
+        let (transmittance, probability) = self.get_transmittance(ray, hit);
+
+        if self.get_exit_reflectance(ray, hit) > rng.random_range(0.0..1.0) {
+            let mut scattered_ray = Ray::new_at_time(
+                hit.point,
+                unit_direction.reflect(hit.normal),
+                ray.get_time(),
+            );
+
+            scattered_ray.specular();
+            return Some((scattered_ray, transmittance/probability));
+        }
+
+        Some((
+            Ray::new_at_time(hit.point, diffuse_direction(-hit.normal, rng), ray.get_time()),
+            transmittance/probability,
+        ))
+    }
+
+    fn eval(
+        &self,
+        ray: &Ray,
+        hit: &HitRecord,
+        direction: DVec3,
+    ) -> Option<(DVec3, f64)> {
+        // Only the walk leaving the medium can be evaluated, the reflection
+        // on entering it is specular.
+        if hit.front_face {
+            return None;
+        }
+
+        let cosine = (-hit.normal).dot(direction.normalize());
+
+        if cosine > 0.0 {
+            let (transmittance, probability) = self.get_transmittance(ray, hit);
+            let transmission = 1.0 - self.get_exit_reflectance(ray, hit);
+
+            Some((
+                transmission*transmittance*cosine/PI,
+                transmission*probability*cosine/PI,
+            ))
+        } else {
+            Some((DVec3::ZERO, 0.0))
+        }
+    }
+}
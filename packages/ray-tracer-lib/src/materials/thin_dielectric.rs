@@ -0,0 +1,90 @@
+use glam::DVec3;
+
+use rand::{
+    Rng,
+    RngCore,
+};
+
+use crate::hitable::HitRecord;
+use crate::ray::Ray;
+
+use super::material::Material;
+use super::microfacet::fresnel_dielectric;
+
+// Infinitely thin sheet of dielectric (e.g. a window pane). Rays are either
+// reflected or go through the surface without being bent, light bouncing
+// back and forth between both interfaces is accounted for.
+#[derive(Clone, Copy, Debug)]
+pub struct ThinDielectric {
+    refraction_index: f64,
+    color: DVec3,
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+pub struct ThinDielectricBuilder {
+    refraction_index: Option<f64>,
+    color: Option<DVec3>,
+}
+
+impl ThinDielectricBuilder {
+    pub fn with_refraction_index(
+        &mut self,
+        value: Option<f64>,
+    ) -> &mut Self {
+        self.refraction_index = value;
+        self
+    }
+
+    // Tint of the transmitted light.
+    pub fn with_color(
+        &mut self,
+        value: Option<DVec3>,
+    ) -> &mut Self {
+        self.color = value;
+        self
+    }
+
+    pub fn build(self) -> ThinDielectric {
+        ThinDielectric {
+            refraction_index: self.refraction_index.unwrap_or(1.5),
+            color: self.color.unwrap_or(DVec3::ONE),
+        }
+    }
+}
+
+impl Default for ThinDielectric {
+    fn default() -> Self {
+        ThinDielectricBuilder::default().build()
+    }
+}
+
+impl Material for ThinDielectric {
+    fn scatter(
+        &self,
+        ray: &Ray,
+        hit: &HitRecord,
+        rng: &mut dyn RngCore,
+    ) -> Option<(Ray, DVec3)> {
+        let unit_direction = ray.get_direction().normalize();
+
+        // Both sides of the sheet behave the same, the normal faces the
+        // incoming ray.
+        let cos_theta = (-unit_direction).dot(hit.normal).min(1.0);
+        let r = fresnel_dielectric(cos_theta, self.refraction_index);
+
+        // Sum of the successive reflections inside the sheet.
+        let reflectance = if r < 1.0 { r + (1.0 - r)*(1.0 - r)*r/(1.0 - r*r) } else { 1.0 };
+
+        if reflectance > rng.random_range(0.0..1.0) {
+            Some((
+                Ray::new_at_time(hit.point, unit_direction.reflect(hit.normal), ray.get_time()),
+                DVec3::ONE,
+            ))
+        } else {
+            Some((
+                Ray::new_at_time(hit.point, unit_direction, ray.get_time()),
+                self.color,
+            ))
+        }
+    }
+}
@@ -0,0 +1,146 @@
+use std::f64::consts::PI;
+use std::sync::Arc;
+
+use glam::DVec3;
+
+use rand::{
+    Rng,
+    RngCore,
+};
+
+use crate::hitable::HitRecord;
+use crate::ray::Ray;
+use crate::textures::{
+    SolidColor,
+    Texture,
+};
+use crate::vector::*;
+
+use super::material::Material;
+
+// Diffuse reflection and diffuse transmission through a thin surface (e.g.
+// paper or leaves). The back side may have its own color.
+#[derive(Clone, Debug)]
+pub struct Translucent {
+    texture: Arc<dyn Texture + Send + Sync>,
+    back_texture: Option<Arc<dyn Texture + Send + Sync>>,
+    transmission: f64,
+}
+
+#[derive(Clone, Default)]
+pub struct TranslucentBuilder {
+    texture: Option<Arc<dyn Texture + Send + Sync>>,
+    back_texture: Option<Arc<dyn Texture + Send + Sync>>,
+    transmission: Option<f64>,
+}
+
+impl TranslucentBuilder {
+    pub fn with_color(
+        &mut self,
+        color: Option<DVec3>,
+    ) -> &mut Self {
+        self.texture = if let Some(color) = color {
+            Some(Arc::new(SolidColor::new(color)))
+        } else {
+            None
+        };
+        self
+    }
+
+    pub fn with_texture(
+        &mut self,
+        texture: Option<Arc<dyn Texture + Send + Sync>>,
+    ) -> &mut Self {
+        self.texture = texture;
+        self
+    }
+
+    // Color of the back side of the surface, defaults to the front one.
+    pub fn with_back_texture(
+        &mut self,
+        texture: Option<Arc<dyn Texture + Send + Sync>>,
+    ) -> &mut Self {
+        self.back_texture = texture;
+        self
+    }
+
+    // Fraction of the light going through the surface.
+    pub fn with_transmission(
+        &mut self,
+        value: Option<f64>,
+    ) -> &mut Self {
+        self.transmission = value;
+        self
+    }
+
+    pub fn build(self) -> Translucent {
+        Translucent {
+            texture: self.texture.unwrap_or(Arc::new(SolidColor::default())),
+            back_texture: self.back_texture,
+            transmission: self.transmission.unwrap_or(0.5).clamp(0.0, 1.0),
+        }
+    }
+}
+
+impl Default for Translucent {
+    fn default() -> Self {
+        TranslucentBuilder::default().build()
+    }
+}
+
+impl Translucent {
+    fn get_color(&self, hit: &HitRecord) -> DVec3 {
+        let texture = match self.back_texture.as_ref() {
+            Some(back_texture) if !hit.front_face => back_texture,
+            _ => &self.texture,
+        };
+
+        texture.get_filtered_color(hit.texture_coordinates, hit.point, hit.footprint)
+    }
+}
+
+impl Material for Translucent {
+    fn scatter(
+        &self,
+        ray: &Ray,
+        hit: &HitRecord,
+        rng: &mut dyn RngCore,
+    ) -> Option<(Ray, DVec3)> {
+        // The normal faces the incoming ray, transmitted rays leave through
+        // the other side.
+        let normal = if rng.random_range(0.0..1.0) < self.transmission {
+            -hit.normal
+        } else {
+            hit.normal
+        };
+
+        let mut scatter_direction = normal + random_unit_vector(rng);
+
+        if scatter_direction.almost_zero(1e-8) {
+            scatter_direction = normal;
+        }
+
+        Some((
+            Ray::new_at_time(hit.point, scatter_direction, ray.get_time()),
+            self.get_color(hit),
+        ))
+    }
+
+    fn eval(
+        &self,
+        _ray: &Ray,
+        hit: &HitRecord,
+        direction: DVec3,
+    ) -> Option<(DVec3, f64)> {
+        let cosine = hit.normal.dot(direction.normalize());
+
+        let weight = if cosine > 0.0 {
+            1.0 - self.transmission
+        } else {
+            self.transmission
+        };
+        let pdf = weight*cosine.abs()/PI;
+
+        Some((self.get_color(hit)*pdf, pdf))
+    }
+}
@@ -1,3 +1,4 @@
+pub mod motion;
 pub mod object;
 pub mod plane;
 pub mod scale;
@@ -5,6 +6,7 @@ pub mod sphere;
 pub mod translate;
 pub mod rotate;
 
+pub use motion::*;
 pub use object::*;
 pub use plane::*;
 pub use scale::*;
@@ -0,0 +1,104 @@
+use std::sync::Arc;
+
+use glam::{
+    DMat3,
+    DVec3,
+};
+
+use crate::aabb::AABB;
+use crate::hitable::*;
+use crate::interval::Interval;
+use crate::ray::Ray;
+
+fn motion_bbox(
+    bbox: &AABB,
+    velocity: DVec3,
+    angular_velocity: DVec3,
+) -> AABB {
+    let mut bbox_t0 = if angular_velocity.length_squared() > 0.0 {
+        // The object spins around its origin, bound the sphere swept by the
+        // corners of its box.
+        let mut r: f64 = 0.0;
+
+        for i in 0..2 {
+            for j in 0..2 {
+                for k in 0..2 {
+                    let x = (i as f64)*bbox.x.max + (1.0 - (i as f64))*bbox.x.min;
+                    let y = (j as f64)*bbox.y.max + (1.0 - (j as f64))*bbox.y.min;
+                    let z = (k as f64)*bbox.z.max + (1.0 - (k as f64))*bbox.z.min;
+
+                    r = r.max(DVec3::new(x, y, z).length());
+                }
+            }
+        }
+
+        AABB::from_points(-r*DVec3::ONE, r*DVec3::ONE)
+    } else {
+        *bbox
+    };
+
+    let bbox_t1 = bbox_t0.translated(velocity);
+
+    bbox_t0.union(&bbox_t1)
+}
+
+#[derive(Clone, Debug)]
+pub struct Motion {
+    object: Arc<dyn Hitable + Send + Sync>,
+    velocity: DVec3,
+    angular_velocity: DVec3,
+    bbox: AABB,
+}
+
+impl Motion {
+    pub fn new(
+        object: Arc<dyn Hitable + Send + Sync>,
+        velocity: DVec3,
+        angular_velocity: DVec3,
+    ) -> Self {
+        let bbox = motion_bbox(&object.bbox(), velocity, angular_velocity);
+
+        Self {
+            object,
+            velocity,
+            angular_velocity,
+            bbox,
+        }
+    }
+
+    fn rotation_mat(&self, time: f64) -> DMat3 {
+        let angle = self.angular_velocity.length()*time;
+
+        if angle != 0.0 {
+            DMat3::from_axis_angle(self.angular_velocity.normalize(), angle)
+        } else {
+            DMat3::IDENTITY
+        }
+    }
+}
+
+impl Hitable for Motion {
+    fn bbox(&self) -> AABB {
+        self.bbox
+    }
+
+    fn hit(&self, ray: &Ray, hit_range: Interval) -> Option<HitRecord> {
+        let time = ray.get_time();
+        let offset = self.velocity*time;
+        let rotation_mat = self.rotation_mat(time);
+        let rotation_mat_inv = rotation_mat.transpose();
+
+        let moved_ray = ray.transformed(
+            rotation_mat_inv*(ray.get_origin() - offset),
+            rotation_mat_inv*ray.get_direction(),
+        );
+
+        self.object.hit(&moved_ray, hit_range)
+            .map(|mut hit| {
+                hit.point = rotation_mat*hit.point + offset;
+                hit.normal = rotation_mat*hit.normal;
+                hit.tangent = rotation_mat*hit.tangent;
+                hit
+            })
+    }
+}
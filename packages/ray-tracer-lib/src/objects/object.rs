@@ -15,10 +15,10 @@ pub enum BVH {
     },
 }
 
-impl Into<Vec<Arc<dyn Hitable + Send + Sync>>> for BVH {
-    fn into(self) -> Vec<Arc<dyn Hitable + Send + Sync>> {
+impl From<BVH> for Vec<Arc<dyn Hitable + Send + Sync>> {
+    fn from(bvh: BVH) -> Self {
         let mut objects =  Vec::new();
-        let mut node_stack = vec![&self];
+        let mut node_stack = vec![&bvh];
 
         while let Some(node) = node_stack.pop() {
             match node {
@@ -38,6 +38,7 @@ pub struct Plane {
     bbox: AABB,
     material: Arc<dyn Material + Send + Sync>,
     normal: DVec3,
+    tangent: DVec3,
     d: f64,
     w: DVec3,
 }
@@ -109,6 +110,7 @@ impl PlaneBuilder {
         let n = u.cross(v);
 
         let normal = n.normalize();
+        let tangent = u.normalize();
 
         let d = normal.dot(p);
         let w = n/(n.dot(n));
@@ -119,6 +121,7 @@ impl PlaneBuilder {
             v,
             shape,
             normal,
+            tangent,
             d,
             w,
             material,
@@ -170,6 +173,11 @@ impl Hitable for Plane {
         let uv = DVec2::new(alpha, beta);
         let material = self.material.clone();
 
-        Some(HitRecord::new_with_uv(ray, material, point, self.normal, uv, t))
+        let mut hit = HitRecord::new_with_tangent(ray, material, point, self.normal, self.tangent, uv, t);
+
+        hit.footprint = ray.get_surface_cone_width(t, self.normal)/self.u.length().min(self.v.length());
+        hit.uv_lengths = DVec2::new(self.u.length(), self.v.length());
+
+        Some(hit).filter(|hit| !hit.material.is_cutout(hit))
     }
 }
@@ -91,16 +91,13 @@ impl Hitable for Rotate {
     fn hit(&self, ray: &Ray, hit_range: Interval) -> Option<HitRecord> {
         let rotated_origin = self.rotation_mat*ray.get_origin();
         let rotated_direction = self.rotation_mat*ray.get_direction();
-        let rotated_ray = Ray::new_at_time(
-            rotated_origin,
-            rotated_direction,
-            ray.get_time()
-        );
+        let rotated_ray = ray.transformed(rotated_origin, rotated_direction);
 
         self.object.hit(&rotated_ray, hit_range)
             .map(|mut hit| {
                 hit.point = self.rotation_mat_inv*hit.point;
                 hit.normal = self.rotation_mat_inv*hit.normal;
+                hit.tangent = self.rotation_mat_inv*hit.tangent;
                 hit
             })
     }
@@ -1,6 +1,6 @@
 use std::sync::Arc;
 
-use glam::{DMat4, DVec3};
+use glam::{DMat4, DVec2, DVec3};
 
 use crate::aabb::AABB;
 use crate::hitable::*;
@@ -71,16 +71,31 @@ impl Hitable for Scale {
     }
 
     fn hit(&self, ray: &Ray, hit_range: Interval) -> Option<HitRecord> {
-        let scaled_ray = Ray::new_at_time(
-             self.scale_matrix_inv.transform_point3(ray.get_origin()),
-             self.scale_matrix_inv.transform_vector3(ray.get_direction()),
-             ray.get_time(),
+        let direction = ray.get_direction();
+        let scaled_direction = self.scale_matrix_inv.transform_vector3(direction);
+
+        let mut scaled_ray = ray.transformed(
+            self.scale_matrix_inv.transform_point3(ray.get_origin()),
+            scaled_direction,
         );
 
+        // The spread follows the scaled direction, the width at the origin
+        // is scaled by the same factor so the cone keeps its shape along the
+        // ray.
+        let factor = scaled_direction.length()/direction.length();
+        scaled_ray.with_cone(factor*ray.get_cone_width(0.0), ray.get_cone_spread());
+
         self.object
             .hit(&scaled_ray, hit_range)
             .map(|mut hit| {
+                let bitangent = hit.normal.cross(hit.tangent);
+
                 hit.point = self.scale_matrix.transform_point3(hit.point);
+                hit.uv_lengths *= DVec2::new(
+                    self.scale_matrix.transform_vector3(hit.tangent).length(),
+                    self.scale_matrix.transform_vector3(bitangent).length(),
+                );
+                hit.tangent = self.scale_matrix.transform_vector3(hit.tangent).normalize();
                 hit
             })
     }
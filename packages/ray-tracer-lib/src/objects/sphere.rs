@@ -126,25 +126,10 @@ impl Hitable for Sphere {
 
         let sqrtd = discriminant.sqrt();
 
-        None
-            .or_else(|| {
-                let t = (h - sqrtd)/a;
-
-                if hit_range.surrounds(t) {
-                    Some(t)
-                } else {
-                    None
-                }
-            })
-            .or_else(|| {
-                let t = (h + sqrtd)/a;
-
-                if hit_range.surrounds(t) {
-                    Some(t)
-                } else {
-                    None
-                }
-            })
+        // The far side is hit when the near one is out of range or cut out.
+        [(h - sqrtd)/a, (h + sqrtd)/a]
+            .into_iter()
+            .filter(|t| hit_range.surrounds(*t))
             .map(|t| {
                 let point = ray.at(t);
                 let material = self.material.clone();
@@ -158,7 +143,22 @@ impl Hitable for Sphere {
                     theta/PI,     // v
                 );
 
-                HitRecord::new_with_uv(ray, material, point, normal, uv, t)
+                // Derivative of the point along u, undefined at the poles.
+                let tangent = DVec3::new(normal.z, 0.0, -normal.x)
+                    .try_normalize()
+                    .unwrap_or_else(|| normal.any_orthonormal_vector());
+
+                let mut hit = HitRecord::new_with_tangent(ray, material, point, normal, tangent, uv, t);
+
+                // Texture coordinates span 2πr along u at the equator and πr
+                // along v.
+                hit.footprint = ray.get_surface_cone_width(t, normal)/(PI*self.radius);
+                hit.uv_lengths = DVec2::new(
+                    2.0*PI*self.radius*theta.sin(),
+                    PI*self.radius,
+                );
+                hit
             })
+            .find(|hit| !hit.material.is_cutout(hit))
     }
 }
@@ -35,10 +35,9 @@ impl Hitable for Translate {
     }
 
     fn hit(&self, ray: &Ray, hit_range: Interval) -> Option<HitRecord> {
-        let translated_ray = Ray::new_at_time(
+        let translated_ray = ray.transformed(
             ray.get_origin() - self.offset,
             ray.get_direction(),
-            ray.get_time(),
         );
 
         self.object.hit(&translated_ray, hit_range)
@@ -1,11 +1,15 @@
 pub use crate::aabb::*;
+pub use crate::aperture::*;
+pub use crate::backgrounds::*;
 pub use crate::camera::*;
 pub use crate::hitable::*;
 pub use crate::image::*;
 pub use crate::interval::*;
+pub use crate::lights::*;
 pub use crate::materials::*;
 pub use crate::objects::*;
 pub use crate::ray::*;
 pub use crate::scene::*;
+pub use crate::spectrum::*;
 pub use crate::textures::*;
 pub use crate::vector::*;
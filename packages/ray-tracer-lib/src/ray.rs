@@ -5,7 +5,14 @@ pub struct Ray {
     origin: DVec3,
     direction: DVec3,
     bounce: usize,
+    specular: bool,
     time: f64,
+    // Wavelength in nanometers carried by the ray when rendering spectrally.
+    wavelength: Option<f64>,
+    // Width of the cone covered by the ray at its origin and its spread
+    // angle, used to filter textures.
+    cone_width: f64,
+    cone_spread: f64,
 }
 
 impl Ray {
@@ -14,13 +21,27 @@ impl Ray {
             origin,
             direction,
             bounce: 0,
+            specular: false,
             time,
+            wavelength: None,
+            cone_width: 0.0,
+            cone_spread: 0.0,
         }
     }
 
     pub fn new(origin: DVec3, direction: DVec3) -> Self {
         Self::new_at_time(origin, direction, 0.0)
     }
+
+    // Copy of the ray with another origin and direction, e.g. expressed in
+    // the space of a transformed object, keeping everything else.
+    pub fn transformed(&self, origin: DVec3, direction: DVec3) -> Self {
+        Self {
+            origin,
+            direction,
+            ..*self
+        }
+    }
 }
 
 impl Ray {
@@ -33,6 +54,28 @@ impl Ray {
         self
     }
 
+    // Marks a ray scattered by a specular (delta) lobe of a material which
+    // also has non specular lobes.
+    pub fn specular(&mut self) -> &mut Self {
+        self.specular = true;
+        self
+    }
+
+    pub fn with_wavelength(&mut self, value: Option<f64>) -> &mut Self {
+        self.wavelength = value;
+        self
+    }
+
+    pub fn with_cone(&mut self, width: f64, spread: f64) -> &mut Self {
+        self.cone_width = width;
+        self.cone_spread = spread;
+        self
+    }
+
+    pub fn is_specular(&self) -> bool {
+        self.specular
+    }
+
     pub fn get_bounce(&self) -> usize {
         self.bounce
     }
@@ -48,4 +91,25 @@ impl Ray {
     pub fn get_time(&self) -> f64 {
         self.time
     }
+
+    // Width of the ray cone at the given parameter.
+    pub fn get_cone_width(&self, t: f64) -> f64 {
+        self.cone_width + self.cone_spread*t*self.direction.length()
+    }
+
+    // Width of the cone where it meets a surface of the given normal, the
+    // footprint stretches at grazing angles.
+    pub fn get_surface_cone_width(&self, t: f64, normal: DVec3) -> f64 {
+        let cosine = self.direction.normalize().dot(normal).abs().max(1e-3);
+
+        self.get_cone_width(t)/cosine
+    }
+
+    pub fn get_cone_spread(&self) -> f64 {
+        self.cone_spread
+    }
+
+    pub fn get_wavelength(&self) -> Option<f64> {
+        self.wavelength
+    }
 }
@@ -1,12 +1,16 @@
+use std::sync::Arc;
+
 use image::Rgb32FImage;
 
 use crate::camera::*;
+use crate::lights::*;
 use crate::objects::*;
 
 #[derive(Clone, Debug)]
 pub struct Scene {
     pub camera: Camera,
     pub objects: BVH,
+    pub lights: Vec<Arc<dyn Light + Send + Sync>>,
 }
 
 impl Scene {
@@ -14,6 +18,6 @@ impl Scene {
         &self,
         progress: Option<P>,
     ) -> Rgb32FImage where P: Fn() + Sync {
-        self.camera.render(&self.objects, progress)
+        self.camera.render(&self.objects, &self.lights, progress)
     }
 }
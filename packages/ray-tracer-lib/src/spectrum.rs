@@ -0,0 +1,113 @@
+use std::sync::LazyLock;
+
+use glam::{
+    DMat3,
+    DVec3,
+};
+
+pub const WAVELENGTH_MIN: f64 = 380.0;
+pub const WAVELENGTH_MAX: f64 = 780.0;
+
+const LUMINANCE: DVec3 = DVec3::new(0.2126, 0.7152, 0.0722);
+
+const XYZ_TO_RGB: DMat3 = DMat3::from_cols_array(&[
+     3.2406, -0.9689,  0.0557,
+    -1.5372,  1.8758, -0.2040,
+    -0.4986,  0.0415,  1.0570,
+]);
+
+fn gaussian(x: f64, mu: f64, sigma_low: f64, sigma_high: f64) -> f64 {
+    let sigma = if x < mu { sigma_low } else { sigma_high };
+    let t = (x - mu)/sigma;
+
+    f64::exp(-0.5*t*t)
+}
+
+// CIE 1931 color matching functions at the given wavelength in nanometers,
+// using the multi-lobe fit of Wyman, Sloan and Shirley.
+pub fn cie_xyz(wavelength: f64) -> DVec3 {
+    let l = wavelength;
+
+    DVec3::new(
+        1.056*gaussian(l, 599.8, 37.9, 31.0)
+            + 0.362*gaussian(l, 442.0, 16.0, 26.7)
+            - 0.065*gaussian(l, 501.1, 20.4, 26.2),
+        0.821*gaussian(l, 568.8, 46.9, 40.5)
+            + 0.286*gaussian(l, 530.9, 16.3, 31.1),
+        1.217*gaussian(l, 437.0, 11.8, 36.0)
+            + 0.681*gaussian(l, 459.0, 26.0, 13.8),
+    )
+}
+
+// Per channel normalization of the film response, so that a constant
+// spectrum integrates to white.
+static FILM_RESPONSE_SCALE: LazyLock<DVec3> = LazyLock::new(|| {
+    let steps = (WAVELENGTH_MAX - WAVELENGTH_MIN) as usize;
+
+    let sum = (0..=steps)
+        .map(|i| film_response(WAVELENGTH_MIN + i as f64))
+        .sum::<DVec3>();
+
+    (steps + 1) as f64/sum
+});
+
+fn film_response(wavelength: f64) -> DVec3 {
+    xyz_to_rgb(cie_xyz(wavelength)).max(DVec3::ZERO)
+}
+
+// Weight of a radiance sample carried at the given wavelength, for
+// wavelengths uniformly sampled between WAVELENGTH_MIN and WAVELENGTH_MAX.
+pub fn spectral_weight(wavelength: f64) -> DVec3 {
+    film_response(wavelength)*(*FILM_RESPONSE_SCALE)
+}
+
+// Relative luminance of a linear sRGB color.
+pub fn luminance(color: DVec3) -> f64 {
+    color.dot(LUMINANCE)
+}
+
+// Decodes an sRGB encoded channel value to linear.
+pub fn srgb_to_linear(value: f64) -> f64 {
+    if value <= 0.04045 {
+        value/12.92
+    } else {
+        ((value + 0.055)/1.055).powf(2.4)
+    }
+}
+
+// Converts CIE XYZ to linear sRGB (D65 white point).
+pub fn xyz_to_rgb(xyz: DVec3) -> DVec3 {
+    XYZ_TO_RGB*xyz
+}
+
+// Planck's law, spectral radiance of a black body at the given wavelength in
+// nanometers and temperature in kelvins.
+pub fn blackbody(wavelength: f64, temperature: f64) -> f64 {
+    const H: f64 = 6.62607015e-34;
+    const C: f64 = 299792458.0;
+    const K: f64 = 1.380649e-23;
+
+    let l = wavelength*1e-9;
+
+    2.0*H*C*C/(l.powi(5)*(f64::exp(H*C/(l*K*temperature)) - 1.0))
+}
+
+// Linear sRGB color of a black body at the given temperature in kelvins,
+// normalized to unit luminance.
+pub fn blackbody_color(temperature: f64) -> DVec3 {
+    let temperature = temperature.max(1.0);
+    let steps = (WAVELENGTH_MAX - WAVELENGTH_MIN) as usize;
+
+    let xyz = (0..=steps)
+        .map(|i| {
+            let wavelength = WAVELENGTH_MIN + i as f64;
+            blackbody(wavelength, temperature)*cie_xyz(wavelength)
+        })
+        .sum::<DVec3>();
+
+    if xyz.y > 0.0 {
+        xyz_to_rgb(xyz/xyz.y).max(DVec3::ZERO)
+    } else {
+        DVec3::ZERO
+    }
+}
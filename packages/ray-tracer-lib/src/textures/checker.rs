@@ -81,10 +81,25 @@ impl Texture for Checker {
     ) -> DVec3 {
         let v = (uv_coord*self.scale).as_u64vec2().dot(U64Vec2::ONE);
 
-        if v%2 == 0 {
+        if v.is_multiple_of(2) {
             self.even_texture.get_color(uv_coord, point)
         } else {
             self.odd_texture.get_color(uv_coord, point)
         }
     }
+
+    fn get_filtered_color(
+        &self,
+        uv_coord: DVec2,
+        point: DVec3,
+        footprint: f64,
+    ) -> DVec3 {
+        let v = (uv_coord*self.scale).as_u64vec2().dot(U64Vec2::ONE);
+
+        if v.is_multiple_of(2) {
+            self.even_texture.get_filtered_color(uv_coord, point, footprint)
+        } else {
+            self.odd_texture.get_filtered_color(uv_coord, point, footprint)
+        }
+    }
 }
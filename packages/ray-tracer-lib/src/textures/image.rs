@@ -1,4 +1,21 @@
-use std::path::Path;
+use std::ops::{
+    Add,
+    Mul,
+};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{
+    Path,
+    PathBuf,
+};
+use std::sync::{
+    Arc,
+    LazyLock,
+    Mutex,
+    OnceLock,
+    PoisonError,
+    Weak,
+};
 
 use glam::{
     DVec2,
@@ -7,35 +24,418 @@ use glam::{
 };
 
 use image::{
+    ColorType,
+    ImageBuffer,
+    ImageDecoder,
     ImageError,
     ImageReader,
+    Luma,
     Rgb32FImage
 };
+use image::imageops::{
+    self,
+    FilterType,
+};
+
+use crate::spectrum::{
+    luminance,
+    srgb_to_linear,
+};
 
 use super::texture::Texture;
 
+type Luma32FImage = ImageBuffer<Luma<f32>, Vec<f32>>;
+
+// Encoding of the colors stored in an image file. Colors are decoded to
+// linear values at load time, data (e.g. normal or height maps) must be
+// read as linear.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum ColorSpace {
+    Srgb,
+    Linear,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Filter {
+    #[default]
+    Nearest,
+    Bilinear,
+    // Bilinear lookups blended between the two mip levels matching the
+    // footprint of the hit.
+    Trilinear,
+}
+
+// How texture coordinates outside of [0, 1] are mapped back to the image.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Wrap {
+    Repeat,
+    Mirror,
+    #[default]
+    Clamp,
+}
+
+impl Wrap {
+    fn apply(self, i: i64, size: u32) -> u32 {
+        let size = size as i64;
+
+        let i = match self {
+            Self::Repeat => i.rem_euclid(size),
+            Self::Mirror => {
+                let i = i.rem_euclid(2*size);
+                if i >= size { 2*size - 1 - i } else { i }
+            },
+            Self::Clamp => i.clamp(0, size - 1),
+        };
+
+        i as u32
+    }
+}
+
+fn bilinear<T, F>(x: f64, y: f64, fetch: F) -> T
+    where
+        T: Add<Output = T> + Mul<f64, Output = T>,
+        F: Fn(i64, i64) -> T,
+{
+    // Texel centers are at half integer coordinates.
+    let x = x - 0.5;
+    let y = y - 0.5;
+
+    let x0 = x.floor();
+    let y0 = y.floor();
+    let tx = x - x0;
+    let ty = y - y0;
+    let (x0, y0) = (x0 as i64, y0 as i64);
+
+    fetch(x0, y0)*((1.0 - tx)*(1.0 - ty))
+        + fetch(x0 + 1, y0)*(tx*(1.0 - ty))
+        + fetch(x0, y0 + 1)*((1.0 - tx)*ty)
+        + fetch(x0 + 1, y0 + 1)*(tx*ty)
+}
+
+// Color space of the file when none is given, only its header is read.
+fn try_get_default_color_space(path: &Path) -> Result<ColorSpace, ImageError> {
+    let color_space = match ImageReader::open(path)?.into_decoder()?.color_type() {
+        ColorType::Rgb32F | ColorType::Rgba32F => ColorSpace::Linear,
+        _ => ColorSpace::Srgb,
+    };
+
+    Ok(color_space)
+}
+
+// Decoded pixels, shared by all the textures reading the same file with the
+// same color space.
+#[derive(Debug)]
+struct ImageData {
+    pixels: Rgb32FImage,
+    // Lower mip levels, each half the size of the previous one. They are
+    // only generated once a trilinear texture uses the image.
+    mipmaps: OnceLock<Vec<Rgb32FImage>>,
+    alpha: Option<Luma32FImage>,
+}
+
+impl ImageData {
+    fn try_load(
+        path: &Path,
+        color_space: ColorSpace,
+    ) -> Result<Self, ImageError> {
+        let image = ImageReader::open(path)?.decode()?;
+
+        let alpha = image.color().has_alpha().then(|| {
+            let rgba = image.to_rgba32f();
+
+            Luma32FImage::from_fn(rgba.width(), rgba.height(), |x, y| {
+                Luma([rgba.get_pixel(x, y).0[3]])
+            })
+        });
+
+        let mut pixels = image.into_rgb32f();
+
+        if color_space == ColorSpace::Srgb {
+            pixels.pixels_mut().flat_map(|pixel| pixel.0.iter_mut()).for_each(|value| {
+                *value = srgb_to_linear(*value as f64) as f32;
+            });
+        }
+
+        Ok(Self {
+            pixels,
+            mipmaps: OnceLock::new(),
+            alpha,
+        })
+    }
+
+    fn get_mipmaps(&self) -> &[Rgb32FImage] {
+        self.mipmaps.get_or_init(|| {
+            let mut mipmaps = Vec::<Rgb32FImage>::new();
+
+            loop {
+                let level = mipmaps.last().unwrap_or(&self.pixels);
+
+                if level.width() <= 1 && level.height() <= 1 {
+                    break mipmaps;
+                }
+
+                let width = (level.width()/2).max(1);
+                let height = (level.height()/2).max(1);
+
+                mipmaps.push(imageops::resize(level, width, height, FilterType::Triangle));
+            }
+        })
+    }
+
+    fn level_count(&self) -> usize {
+        1 + self.mipmaps.get().map_or(0, Vec::len)
+    }
+
+    fn get_level(&self, level: usize) -> &Rgb32FImage {
+        match level {
+            0 => &self.pixels,
+            _ => &self.get_mipmaps()[level - 1],
+        }
+    }
+}
+
+// Images loaded with their default color space are also registered without
+// one, so that later lookups do not read the file header again.
+type ImageCacheKey = (PathBuf, Option<ColorSpace>);
+
+// Process wide cache of the decoded images, entries live as long as some
+// texture uses them.
+static IMAGE_CACHE: LazyLock<Mutex<HashMap<ImageCacheKey, Weak<ImageData>>>> =
+    LazyLock::new(Default::default);
+
+fn get_cached_image_data(key: &ImageCacheKey) -> Option<Arc<ImageData>> {
+    IMAGE_CACHE
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .get(key)
+        .and_then(Weak::upgrade)
+}
+
+fn try_load_image_data(
+    path: PathBuf,
+    color_space: ColorSpace,
+) -> Result<Arc<ImageData>, ImageError> {
+    let key = (path, Some(color_space));
+
+    if let Some(data) = get_cached_image_data(&key) {
+        return Ok(data);
+    }
+
+    // Decoding happens without holding the lock, another texture may have
+    // loaded the same image in the meantime.
+    let data = Arc::new(ImageData::try_load(&key.0, color_space)?);
+    let mut cache = IMAGE_CACHE.lock().unwrap_or_else(PoisonError::into_inner);
+
+    match cache.get(&key).and_then(Weak::upgrade) {
+        Some(data) => Ok(data),
+        None => {
+            cache.retain(|_, data| data.strong_count() > 0);
+            cache.insert(key, Arc::downgrade(&data));
+            Ok(data)
+        },
+    }
+}
+
 #[derive(Clone, Debug)]
 pub struct Image {
-    image: Rgb32FImage,
+    data: Arc<ImageData>,
+    filter: Filter,
+    wrap: Wrap,
+    scale: DVec2,
+    offset: DVec2,
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+pub struct ImageBuilder {
+    color_space: Option<ColorSpace>,
+    filter: Option<Filter>,
+    wrap: Option<Wrap>,
+    scale: Option<DVec2>,
+    offset: Option<DVec2>,
+}
+
+impl ImageBuilder {
+    // Defaults to sRGB for 8 and 16 bits images and to linear for floating
+    // point ones (e.g. HDR or OpenEXR).
+    pub fn with_color_space(
+        &mut self,
+        value: Option<ColorSpace>,
+    ) -> &mut Self {
+        self.color_space = value;
+        self
+    }
+
+    pub fn with_filter(
+        &mut self,
+        value: Option<Filter>,
+    ) -> &mut Self {
+        self.filter = value;
+        self
+    }
+
+    pub fn with_wrap(
+        &mut self,
+        value: Option<Wrap>,
+    ) -> &mut Self {
+        self.wrap = value;
+        self
+    }
+
+    // Texture coordinates are scaled then offset before the lookup.
+    pub fn with_scale(
+        &mut self,
+        value: Option<DVec2>,
+    ) -> &mut Self {
+        self.scale = value;
+        self
+    }
+
+    pub fn with_offset(
+        &mut self,
+        value: Option<DVec2>,
+    ) -> &mut Self {
+        self.offset = value;
+        self
+    }
+
+    pub fn try_build<P: AsRef<Path>>(self, path: P) -> Result<Image, ImageError> {
+        let filter = self.filter.unwrap_or_default();
+        let path = fs::canonicalize(path.as_ref())?;
+
+        let data = match get_cached_image_data(&(path.clone(), self.color_space)) {
+            Some(data) => data,
+            None => match self.color_space {
+                Some(color_space) => try_load_image_data(path, color_space)?,
+                None => {
+                    let color_space = try_get_default_color_space(&path)?;
+                    let data = try_load_image_data(path.clone(), color_space)?;
+
+                    IMAGE_CACHE
+                        .lock()
+                        .unwrap_or_else(PoisonError::into_inner)
+                        .insert((path, None), Arc::downgrade(&data));
+                    data
+                },
+            },
+        };
+
+        if filter == Filter::Trilinear {
+            data.get_mipmaps();
+        }
+
+        Ok(Image {
+            data,
+            filter,
+            wrap: self.wrap.unwrap_or_default(),
+            scale: self.scale.unwrap_or(DVec2::ONE),
+            offset: self.offset.unwrap_or(DVec2::ZERO),
+        })
+    }
 }
 
 impl Image {
     pub fn try_from_path<P: AsRef<Path>>(path: P) -> Result<Self, ImageError> {
-        let image = ImageReader::open(path.as_ref())?.decode()?.into_rgb32f();
+        ImageBuilder::default().try_build(path)
+    }
+}
+
+impl Image {
+    pub fn width(&self) -> u32 {
+        self.data.pixels.width()
+    }
+
+    pub fn height(&self) -> u32 {
+        self.data.pixels.height()
+    }
+
+    pub fn get_pixel(&self, x: u32, y: u32) -> DVec3 {
+        Vec3::from_array(self.data.pixels.get_pixel(x, y).0).as_dvec3()
+    }
+
+    // Continuous pixel coordinates of the given texture coordinates, v
+    // going up the image.
+    fn get_pixel_coordinates(&self, uv_coord: DVec2, width: u32, height: u32) -> (f64, f64) {
+        let uv = uv_coord*self.scale + self.offset;
+
+        (uv.x*(width as f64), (1.0 - uv.y)*(height as f64))
+    }
+
+    fn fetch(&self, level: usize, x: i64, y: i64) -> DVec3 {
+        let image = self.data.get_level(level);
+        let x = self.wrap.apply(x, image.width());
+        let y = self.wrap.apply(y, image.height());
+
+        Vec3::from_array(image.get_pixel(x, y).0).as_dvec3()
+    }
+
+    fn sample_level(&self, level: usize, uv_coord: DVec2, filter: Filter) -> DVec3 {
+        let image = self.data.get_level(level);
+        let (x, y) = self.get_pixel_coordinates(uv_coord, image.width(), image.height());
 
-        Ok(Self { image })
+        if filter == Filter::Nearest {
+            self.fetch(level, x.floor() as i64, y.floor() as i64)
+        } else {
+            bilinear(x, y, |x, y| self.fetch(level, x, y))
+        }
     }
 }
 
 impl Texture for Image {
     fn get_color(
+        &self,
+        uv_coord: DVec2,
+        point: DVec3,
+    ) -> DVec3 {
+        self.get_filtered_color(uv_coord, point, 0.0)
+    }
+
+    fn get_filtered_color(
         &self,
         uv_coord: DVec2,
         _: DVec3,
+        footprint: f64,
     ) -> DVec3 {
-        let x = (uv_coord.x.clamp(0., 1.)*(self.image.width() as f64)) as u32;
-        let y = ((1.0 - uv_coord.y.clamp(0., 1.))*(self.image.height() as f64)) as u32;
+        if self.filter != Filter::Trilinear {
+            return self.sample_level(0, uv_coord, self.filter);
+        }
+
+        // Level whose texels are as wide as the footprint.
+        let texels = footprint*(self.scale.abs().max_element())*(self.width().max(self.height()) as f64);
+        let lod = texels.max(1.0).log2().min((self.data.level_count() - 1) as f64);
+
+        let level = lod.floor() as usize;
+        let t = lod - level as f64;
+        let color = self.sample_level(level, uv_coord, Filter::Bilinear);
+
+        if t > 0.0 {
+            (1.0 - t)*color + t*self.sample_level(level + 1, uv_coord, Filter::Bilinear)
+        } else {
+            color
+        }
+    }
+
+    fn get_alpha(
+        &self,
+        uv_coord: DVec2,
+        point: DVec3,
+    ) -> f64 {
+        match self.data.alpha.as_ref() {
+            Some(alpha) => {
+                let (x, y) = self.get_pixel_coordinates(uv_coord, alpha.width(), alpha.height());
+                let fetch = |x: i64, y: i64| {
+                    let x = self.wrap.apply(x, alpha.width());
+                    let y = self.wrap.apply(y, alpha.height());
+
+                    alpha.get_pixel(x, y).0[0] as f64
+                };
 
-        Vec3::from_array(self.image.get_pixel(x, y).0).as_dvec3()
+                if self.filter == Filter::Nearest {
+                    fetch(x.floor() as i64, y.floor() as i64)
+                } else {
+                    bilinear(x, y, fetch)
+                }
+            },
+            None => luminance(self.get_color(uv_coord, point)),
+        }
     }
 }
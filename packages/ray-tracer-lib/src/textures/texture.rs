@@ -5,6 +5,20 @@ use glam::{
     DVec3,
 };
 
+use crate::spectrum::luminance;
+
 pub trait Texture: Debug {
     fn get_color(&self, uv_coord: DVec2, point: DVec3) -> DVec3;
+
+    // Color averaged over a footprint of the given width in texture
+    // coordinates, textures which are not prefiltered ignore it.
+    fn get_filtered_color(&self, uv_coord: DVec2, point: DVec3, _footprint: f64) -> DVec3 {
+        self.get_color(uv_coord, point)
+    }
+
+    // Opacity in [0, 1], textures without an alpha channel use their
+    // luminance.
+    fn get_alpha(&self, uv_coord: DVec2, point: DVec3) -> f64 {
+        luminance(self.get_color(uv_coord, point))
+    }
 }
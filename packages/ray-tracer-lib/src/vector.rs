@@ -69,13 +69,23 @@ pub fn random_in_unit_sphere(rng: &mut dyn RngCore) -> DVec3 {
     }
 }
 
+pub fn random_unit_vector(rng: &mut dyn RngCore) -> DVec3 {
+    loop {
+        let p = DVec3::from_rng_ranged(rng, -1.0..1.0);
+        let length_squared = p.length_squared();
+
+        if 1e-160 < length_squared && length_squared <= 1.0 {
+            break p/length_squared.sqrt()
+        }
+    }
+}
+
 pub fn random_in_unit_disk(rng: &mut dyn RngCore) -> DVec3 {
     loop {
         let p = DVec2::from_rng_ranged(rng, -1.0..1.0).extend(0.0);
-        let length_squared = p.length_squared();
 
-        if length_squared < 1.0 {
-            break p/length_squared
+        if p.length_squared() < 1.0 {
+            break p
         }
     }
 }
@@ -1,7 +1,14 @@
 use std::borrow::Cow;
 use std::f64::consts::PI;
+use std::fmt;
 use std::fs::File;
-use std::path::PathBuf;
+use std::ops::RangeInclusive;
+use std::path::{
+    Path,
+    PathBuf,
+};
+use std::str::FromStr;
+use std::sync::Arc;
 use std::time::Duration;
 
 use anyhow::Result;
@@ -30,7 +37,11 @@ use serde::{
     Deserialize,
     Serialize,
 };
-use serde_with::skip_serializing_none;
+use serde_with::{
+    DeserializeFromStr,
+    SerializeDisplay,
+    skip_serializing_none,
+};
 
 use thiserror::Error;
 
@@ -45,6 +56,15 @@ pub enum CliError {
     #[error("Invalid image ratio: '{0}'")]
     InvalidRatioArgument(String),
 
+    #[error("Invalid frame range: '{0}'")]
+    InvalidFramesArgument(String),
+
+    #[error("Invalid focus target: '{0}'")]
+    InvalidFocusArgument(String),
+
+    #[error("Cannot load aperture mask '{0}': {1}")]
+    ApertureMaskError(PathBuf, anyhow::Error),
+
     #[error(
         "When '{}' or '{}' are specified, one of '{}', '{}', '{}', '{}' must be specified too.",
         cformat!("<yellow>{}</yellow>", .0),
@@ -108,6 +128,68 @@ fn parse_aspect_ratio(mut s: &str) -> Result<f64, CliError> {
     }
 }
 
+pub fn parse_frames(s: &str) -> Result<RangeInclusive<usize>, CliError> {
+    static RE: Lazy<Regex> = Lazy::new(|| {
+        Regex::new(r"^(\d+)(?:\s*\.\.=?\s*(\d+))?$").unwrap()
+    });
+
+    let captures = RE.captures(s.trim()).ok_or_else(|| CliError::InvalidFramesArgument(s.into()))?;
+
+    let first = captures.get(1).unwrap().as_str().parse::<usize>()
+        .map_err(|_| CliError::InvalidFramesArgument(s.into()))?;
+    let last = captures.get(2).map(|m| m.as_str().parse::<usize>()).transpose()
+        .map_err(|_| CliError::InvalidFramesArgument(s.into()))?
+        .unwrap_or(first);
+
+    if first <= last {
+        Ok(first..=last)
+    } else {
+        Err(CliError::InvalidFramesArgument(s.into()))
+    }
+}
+
+#[derive(Clone, Copy, Debug, DeserializeFromStr, SerializeDisplay)]
+pub enum FocusOn {
+    Auto(Option<(usize, usize)>),
+    Point(DVec3),
+}
+
+impl FromStr for FocusOn {
+    type Err = CliError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        static RE: Lazy<Regex> = Lazy::new(|| {
+            Regex::new(r"^auto(?::\s*(\d+)\s*,\s*(\d+))?$").unwrap()
+        });
+
+        if let Some(captures) = RE.captures(s.trim()) {
+            let pixel = match (captures.get(1), captures.get(2)) {
+                (Some(x), Some(y)) => Some((
+                    x.as_str().parse::<usize>().map_err(|_| CliError::InvalidFocusArgument(s.into()))?,
+                    y.as_str().parse::<usize>().map_err(|_| CliError::InvalidFocusArgument(s.into()))?,
+                )),
+                _ => None,
+            };
+
+            Ok(Self::Auto(pixel))
+        } else {
+            parse_vector(s)
+                .map(Self::Point)
+                .map_err(|_| CliError::InvalidFocusArgument(s.into()))
+        }
+    }
+}
+
+impl fmt::Display for FocusOn {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Auto(None) => write!(f, "auto"),
+            Self::Auto(Some((x, y))) => write!(f, "auto:{x},{y}"),
+            Self::Point(p) => write!(f, "{},{},{}", p.x, p.y, p.z),
+        }
+    }
+}
+
 #[derive(clap::Args, Debug)]
 #[group(id = "Image")]
 pub struct ImageConfig {
@@ -137,9 +219,7 @@ pub struct ImageConfig {
 }
 
 impl ImageConfig {
-    pub fn get_file(&self) -> Result<(File, ImageFormat)> {
-        let output = self.output.as_path();
-
+    fn open_file(&self, output: &Path) -> Result<(File, ImageFormat)> {
         let format = ImageFormat::from_path(output)?;
         let file =
             File::options()
@@ -152,9 +232,35 @@ impl ImageConfig {
 
         Ok((file, format))
     }
+
+    pub fn get_file(&self) -> Result<(File, ImageFormat)> {
+        self.open_file(self.output.as_path())
+    }
+
+    pub fn get_frame_file(&self, frame: usize) -> Result<(File, ImageFormat)> {
+        let stem = self.output.file_stem().unwrap_or_default().to_string_lossy();
+        let mut file_name = format!("{stem}_{frame:04}");
+
+        if let Some(ext) = self.output.extension() {
+            file_name.push('.');
+            file_name.push_str(&ext.to_string_lossy());
+        }
+
+        self.open_file(self.output.with_file_name(file_name).as_path())
+    }
+}
+
+#[derive(clap::ValueEnum, Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ProjectionConfig {
+    #[default]
+    Perspective,
+    Orthographic,
+    Equirectangular,
+    Fisheye,
 }
 
-#[derive(clap::Args, Clone, Copy, Debug, Default, Deserialize, Serialize)]
+#[derive(clap::Args, Clone, Debug, Default, Deserialize, Serialize)]
 #[group(id = "camera")]
 #[skip_serializing_none]
 pub struct CameraConfig {
@@ -220,7 +326,8 @@ pub struct CameraConfig {
     )]
     pub view_up: Option<DVec3>,
 
-    /// Specify the camera focal length.
+    /// Specify the camera focal length in millimeters, when specified the
+    /// field of view is derived from the focal length and the sensor width.
     #[arg(
         env = "NR_RT_CAMERA_FOCAL_LENGTH",
         long,
@@ -228,7 +335,52 @@ pub struct CameraConfig {
     )]
     pub focal_length: Option<f64>,
 
-    /// Specify the camera focal length.
+    /// Specify the camera sensor width in millimeters.
+    #[arg(
+        env = "NR_RT_CAMERA_SENSOR_WIDTH",
+        long,
+        value_name = "SENSOR_WIDTH",
+    )]
+    pub sensor_width: Option<f64>,
+
+    /// Specify the camera f-number, when specified the aperture is derived
+    /// from the focal length and the f-number instead of the defocus angle.
+    /// Without a focal length, the one matching the field of view and the
+    /// sensor width is used. Scene units are assumed to be meters.
+    #[arg(
+        env = "NR_RT_CAMERA_F_NUMBER",
+        long,
+        value_name = "F_NUMBER",
+    )]
+    pub f_number: Option<f64>,
+
+    /// Specify the number of blades of the aperture, the aperture is circular
+    /// when not specified.
+    #[arg(
+        env = "NR_RT_CAMERA_APERTURE_BLADES",
+        long,
+        value_name = "COUNT",
+    )]
+    pub aperture_blades: Option<usize>,
+
+    /// Specify the rotation of the aperture blades in degrees.
+    #[arg(
+        env = "NR_RT_CAMERA_APERTURE_ROTATION",
+        long,
+        value_name = "ANGLE",
+    )]
+    pub aperture_rotation: Option<f64>,
+
+    /// Specify a grayscale image used as the aperture shape, overrides the
+    /// aperture blades.
+    #[arg(
+        env = "NR_RT_CAMERA_APERTURE_MASK",
+        long,
+        value_name = "FILE",
+    )]
+    pub aperture_mask: Option<PathBuf>,
+
+    /// Specify the camera field of view.
     #[arg(
         env = "NR_RT_CAMERA_FIELD_OF_VIEW",
         long,
@@ -236,6 +388,31 @@ pub struct CameraConfig {
     )]
     pub field_of_view: Option<f64>,
 
+    /// Specify the camera projection.
+    #[arg(
+        env = "NR_RT_CAMERA_PROJECTION",
+        long,
+        value_name = "PROJECTION",
+    )]
+    pub projection: Option<ProjectionConfig>,
+
+    /// Specify the height of the view with the orthographic projection.
+    #[arg(
+        env = "NR_RT_CAMERA_VIEW_HEIGHT",
+        long,
+        value_name = "HEIGHT",
+    )]
+    pub view_height: Option<f64>,
+
+    /// Specify the angle in degrees covered by the image circle with the
+    /// fisheye projection, the field of view is not used by this projection.
+    #[arg(
+        env = "NR_RT_CAMERA_FISHEYE_ANGLE",
+        long,
+        value_name = "ANGLE",
+    )]
+    pub fisheye_angle: Option<f64>,
+
     /// Specify the defocus angle.
     #[arg(
         env = "NR_RT_CAMERA_DEFOCUS_ANGLE",
@@ -252,6 +429,43 @@ pub struct CameraConfig {
     )]
     pub focus_distance: Option<f64>,
 
+    /// Specify what the camera focuses on, either a position or 'auto' to
+    /// focus on the first object hit through the center of the image or
+    /// 'auto:X,Y' through the given pixel. Overrides the focus distance.
+    #[arg(
+        env = "NR_RT_CAMERA_FOCUS_ON",
+        long,
+        value_name = "FOCUS_ON",
+    )]
+    pub focus_on: Option<FocusOn>,
+
+    /// Specify when the shutter opens, motions are defined between 0 and 1.
+    #[arg(
+        env = "NR_RT_CAMERA_SHUTTER_OPEN",
+        long,
+        value_name = "TIME",
+    )]
+    pub shutter_open: Option<f64>,
+
+    /// Specify when the shutter closes, motions are defined between 0 and 1.
+    #[arg(
+        env = "NR_RT_CAMERA_SHUTTER_CLOSE",
+        long,
+        value_name = "TIME",
+    )]
+    pub shutter_close: Option<f64>,
+
+    /// Trace a single wavelength per sample so that dielectrics with a
+    /// dispersion split light into its colors.
+    #[arg(
+        env = "NR_RT_CAMERA_SPECTRAL",
+        long,
+        num_args = 0..=1,
+        default_missing_value = "true",
+        value_name = "BOOL",
+    )]
+    pub spectral: Option<bool>,
+
     /// Specify how many samples per pixels anti-aliasing will use.
     #[arg(
         env = "NR_RT_CAMERA_SAMPLES_PER_PIXEL",
@@ -326,15 +540,54 @@ impl CameraConfig {
         if let Some(aspect_ratio) = other.aspect_ratio {
             self.aspect_ratio.replace(aspect_ratio);
         }
+        if let Some(focal_length) = other.focal_length {
+            self.focal_length.replace(focal_length);
+        }
+        if let Some(sensor_width) = other.sensor_width {
+            self.sensor_width.replace(sensor_width);
+        }
+        if let Some(f_number) = other.f_number {
+            self.f_number.replace(f_number);
+        }
+        if let Some(aperture_blades) = other.aperture_blades {
+            self.aperture_blades.replace(aperture_blades);
+        }
+        if let Some(aperture_rotation) = other.aperture_rotation {
+            self.aperture_rotation.replace(aperture_rotation);
+        }
+        if let Some(aperture_mask) = other.aperture_mask.as_ref() {
+            self.aperture_mask.replace(aperture_mask.clone());
+        }
         if let Some(field_of_view) = other.field_of_view {
             self.field_of_view.replace(field_of_view);
         }
+        if let Some(projection) = other.projection {
+            self.projection.replace(projection);
+        }
+        if let Some(view_height) = other.view_height {
+            self.view_height.replace(view_height);
+        }
+        if let Some(fisheye_angle) = other.fisheye_angle {
+            self.fisheye_angle.replace(fisheye_angle);
+        }
         if let Some(focus_distance) = other.focus_distance {
             self.focus_distance.replace(focus_distance);
         }
+        if let Some(focus_on) = other.focus_on {
+            self.focus_on.replace(focus_on);
+        }
         if let Some(defocus_angle) = other.defocus_angle {
             self.defocus_angle.replace(defocus_angle);
         }
+        if let Some(shutter_open) = other.shutter_open {
+            self.shutter_open.replace(shutter_open);
+        }
+        if let Some(shutter_close) = other.shutter_close {
+            self.shutter_close.replace(shutter_close);
+        }
+        if let Some(spectral) = other.spectral {
+            self.spectral.replace(spectral);
+        }
         if let Some(samples_per_pixel) = other.samples_per_pixel {
             self.samples_per_pixel.replace(samples_per_pixel);
         }
@@ -370,14 +623,76 @@ impl CameraConfig {
             config.with_field_of_view((field_of_view*PI)/180.0);
         }
 
+        if let Some(focal_length) = self.focal_length {
+            config.with_focal_length(focal_length);
+        }
+
+        if let Some(sensor_width) = self.sensor_width {
+            config.with_sensor_width(sensor_width);
+        }
+
+        if let Some(f_number) = self.f_number {
+            config.with_f_number(f_number);
+        }
+
+        if let Some(aperture_mask) = self.aperture_mask.as_ref() {
+            let mask = ApertureMask::try_from_path(aperture_mask)
+                .map_err(|err| CliError::ApertureMaskError(aperture_mask.clone(), err))?;
+
+            config.with_aperture(Aperture::Mask(Arc::new(mask)));
+        } else if let Some(blades) = self.aperture_blades {
+            let rotation = self.aperture_rotation.unwrap_or(0.0);
+
+            config.with_aperture(Aperture::Polygon {
+                blades,
+                rotation: (rotation*PI)/180.0,
+            });
+        }
+
+        if let Some(projection) = self.projection {
+            config.with_projection(match projection {
+                ProjectionConfig::Perspective => {
+                    Projection::Perspective
+                },
+                ProjectionConfig::Orthographic => {
+                    let view_height = self.view_height.unwrap_or(CameraBuilder::DEFAULT_VIEW_HEIGHT);
+
+                    Projection::Orthographic { view_height }
+                },
+                ProjectionConfig::Equirectangular => {
+                    Projection::Equirectangular
+                },
+                ProjectionConfig::Fisheye => {
+                    let fisheye_angle = self.fisheye_angle.unwrap_or(DEFAULT_FISHEYE_ANGLE);
+
+                    Projection::Fisheye { field_of_view: (fisheye_angle*PI)/180.0 }
+                },
+            });
+        }
+
         if let Some(focus_distance) = self.focus_distance {
             config.with_focus_dist(focus_distance);
         }
 
+        if let Some(FocusOn::Point(focus_point)) = self.focus_on {
+            config.with_focus_point(focus_point);
+        }
+
         if let Some(defocus_angle) = self.defocus_angle {
             config.with_defocus_angle((defocus_angle*PI)/180.0);
         }
 
+        if self.shutter_open.is_some() || self.shutter_close.is_some() {
+            config.with_shutter(Interval::new(
+                self.shutter_open.unwrap_or(CameraBuilder::DEFAULT_SHUTTER.min),
+                self.shutter_close.unwrap_or(CameraBuilder::DEFAULT_SHUTTER.max),
+            ));
+        }
+
+        if let Some(spectral) = self.spectral {
+            config.with_spectral(spectral);
+        }
+
         if let Some(samples_per_pixel) = self.samples_per_pixel {
             config.with_samples_per_pixel(samples_per_pixel);
         }
@@ -415,11 +730,9 @@ pub fn get_progress(
         ProgressStyle::with_template(PROGRESS_TEMPLATE)
             .map(|style| style.progress_chars("#>-"))
             .map(|style| {
-                let bar =
-                    ProgressBar::no_length()
-                        .with_style(style)
-                        .with_prefix(prefix);
-                bar
+                ProgressBar::no_length()
+                    .with_style(style)
+                    .with_prefix(prefix)
             })
             .ok()
     } else {
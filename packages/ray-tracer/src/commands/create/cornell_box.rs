@@ -48,7 +48,13 @@ fn generate_light(
         id.clone(),
         MaterialConfig::DiffuseLight {
             texture: Some(texture),
-            intensity,
+            intensity: Some(intensity),
+            power: None,
+            temperature: None,
+            two_sided: None,
+            profile: None,
+            profile_axis: None,
+            profile_up: None,
         }
     ));
     id
@@ -1,3 +1,5 @@
+use std::path::Path;
+
 use anyhow::Result;
 
 use glam::DVec3;
@@ -9,6 +11,13 @@ use super::create::*;
 
 pub fn run(args: &CreateArgs) -> Result<()> {
     const GROUND_SPHERE_RADIUS: f64 = 1000.0;
+    // Grayscale elevation from sea level (black) to 6400 m (white), e.g. the
+    // public domain NASA Blue Marble topography resampled to 2048x1024. It
+    // is not shipped, the relief is left out when missing.
+    const EARTH_ELEVATION_PATH: &str = "scenes/textures/earth_elevation.png";
+    // The earth is 10 units wide for 6371 km, the relief is exaggerated ten
+    // times.
+    const EARTH_ELEVATION_STRENGTH: f64 = 0.1;
 
     let mut scene_config = SceneConfig::default();
 
@@ -27,6 +36,8 @@ pub fn run(args: &CreateArgs) -> Result<()> {
     let ground_id = Box::<str>::from("ground");
     let earth_id = Box::<str>::from("earth");
     let moon_id = Box::<str>::from("moon");
+    let earth_surface_id = Box::<str>::from("earth-surface");
+    let earth_elevation_id = Box::<str>::from("earth-elevation");
 
     scene_config.textures.push((
         ground_id.clone(),
@@ -34,21 +45,61 @@ pub fn run(args: &CreateArgs) -> Result<()> {
     ));
     scene_config.textures.push((
         earth_id.clone(),
-        TextureConfig::Image { path: "scenes/textures/earth.jpg".into(), },
+        TextureConfig::Image {
+            path: "scenes/textures/earth.jpg".into(),
+            color_space: None,
+            filter: Some(ImageFilterConfig::Trilinear),
+            wrap: Some(ImageWrapConfig::Repeat),
+            scale: None,
+            offset: None,
+        },
     ));
     scene_config.textures.push((
         moon_id.clone(),
-        TextureConfig::Image { path: "scenes/textures/moon.jpg".into(), },
+        TextureConfig::Image {
+            path: "scenes/textures/moon.jpg".into(),
+            color_space: None,
+            filter: Some(ImageFilterConfig::Trilinear),
+            wrap: Some(ImageWrapConfig::Repeat),
+            scale: None,
+            offset: None,
+        },
     ));
 
     scene_config.materials.push((
         ground_id.clone(),
         MaterialConfig::Lambertian { texture: Some(ground_id.clone()) },
     ));
-    scene_config.materials.push((
-        earth_id.clone(),
-        MaterialConfig::Lambertian { texture: Some(earth_id.clone()) },
-    ));
+    if Path::new(EARTH_ELEVATION_PATH).exists() {
+        scene_config.textures.push((
+            earth_elevation_id.clone(),
+            TextureConfig::Image {
+                path: EARTH_ELEVATION_PATH.into(),
+                color_space: Some(ColorSpaceConfig::Linear),
+                filter: Some(ImageFilterConfig::Bilinear),
+                wrap: Some(ImageWrapConfig::Repeat),
+                scale: None,
+                offset: None,
+            },
+        ));
+        scene_config.materials.push((
+            earth_surface_id.clone(),
+            MaterialConfig::Lambertian { texture: Some(earth_id.clone()) },
+        ));
+        scene_config.materials.push((
+            earth_id.clone(),
+            MaterialConfig::BumpMap {
+                material: earth_surface_id.clone(),
+                texture: earth_elevation_id.clone(),
+                strength: Some(EARTH_ELEVATION_STRENGTH),
+            },
+        ));
+    } else {
+        scene_config.materials.push((
+            earth_id.clone(),
+            MaterialConfig::Lambertian { texture: Some(earth_id.clone()) },
+        ));
+    }
     scene_config.materials.push((
         moon_id.clone(),
         MaterialConfig::Lambertian { texture: Some(moon_id.clone()) },
@@ -57,16 +108,19 @@ pub fn run(args: &CreateArgs) -> Result<()> {
     scene_config.scene.push(ObjectConfig::Sphere {
         center: GROUND_SPHERE_RADIUS*DVec3::NEG_Y,
         radius: GROUND_SPHERE_RADIUS,
+        speed: None,
         material: Some(ground_id.clone()),
     });
     scene_config.scene.push(ObjectConfig::Sphere {
         center: DVec3::new(0., 10., 0.),
         radius: 10.,
+        speed: None,
         material: Some(earth_id.clone()),
     });
     scene_config.scene.push(ObjectConfig::Sphere {
         center: DVec3::new(-12., 12., -20.),
         radius: 3.,
+        speed: None,
         material: Some(moon_id.clone()),
     });
 
@@ -18,7 +18,7 @@ pub fn run(args: &CreateArgs) -> Result<()> {
             look_from: Some(DVec3::new(30., 20., -60.)),
             look_at: Some(DVec3::new(20., 10., -20.)),
             field_of_view: Some(30.),
-            ray_max_bounces: Some(10),
+            ray_max_bounces: Some(64),
             samples_per_pixel: Some(10),
             ..CameraConfig::default()
         })
@@ -38,6 +38,7 @@ pub fn run(args: &CreateArgs) -> Result<()> {
         scene_config.scene.push(ObjectConfig::Sphere {
             center: GROUND_SPHERE_RADIUS*DVec3::NEG_Y,
             radius: GROUND_SPHERE_RADIUS,
+            speed: None,
             material: Some(id.clone()),
         });
     } {
@@ -57,12 +58,15 @@ pub fn run(args: &CreateArgs) -> Result<()> {
             id.clone(),
             MaterialConfig::Metal {
                 texture: Some(id.clone()),
-                fuzz: 0.05,
+                roughness: Some(0.05),
+                anisotropy: None,
+                conductor: None,
             },
         ));
         scene_config.scene.push(ObjectConfig::Sphere {
             center: DVec3::new(-30., 10., 10.),
             radius: 10.,
+            speed: None,
             material: Some(id.clone()),
         });
     } {
@@ -77,14 +81,17 @@ pub fn run(args: &CreateArgs) -> Result<()> {
         ));
         scene_config.materials.push((
             id.clone(),
-            MaterialConfig::Metal {
+            MaterialConfig::Subsurface {
+                albedo: None,
                 texture: Some(id.clone()),
-                fuzz: 0.9,
+                mean_free_path: Some(DVec3::new(4.0, 2.5, 1.5)),
+                refraction_index: None,
             }
         ));
         scene_config.scene.push(ObjectConfig::Sphere {
             center: DVec3::new(20., 10., -20.),
             radius: 10.,
+            speed: None,
             material: Some(id.clone()),
         });
     } {
@@ -98,12 +105,15 @@ pub fn run(args: &CreateArgs) -> Result<()> {
             id.clone(),
             MaterialConfig::Metal {
                 texture: Some(id.clone()),
-                fuzz: 0.8,
+                roughness: Some(0.8),
+                anisotropy: None,
+                conductor: None,
             },
         ));
         scene_config.scene.push(ObjectConfig::Sphere {
             center: DVec3::new(10., 10., 25.),
             radius: 10.,
+            speed: None,
             material: Some(id.clone()),
         });
     }
@@ -39,8 +39,11 @@ fn generate_marble(
     ));
     scene_config.materials.push((
         mat_id.clone(),
-        MaterialConfig::Lambertian {
+        MaterialConfig::Subsurface {
+            albedo: None,
             texture: Some(tex_id),
+            mean_free_path: Some(DVec3::new(0.8, 0.5, 0.3)),
+            refraction_index: None,
         },
     ));
     mat_id
@@ -61,7 +64,13 @@ fn generate_light(
     scene_config.materials.push((
         mat_id.clone(),
         MaterialConfig::DiffuseLight {
-            intensity,
+            intensity: Some(intensity),
+            power: None,
+            temperature: None,
+            two_sided: None,
+            profile: None,
+            profile_axis: None,
+            profile_up: None,
             texture: Some(tex_id),
         },
     ));
@@ -80,6 +89,7 @@ fn generate_objects(
         scene_config.scene.push(ObjectConfig::Sphere {
             center: GROUND_SPHERE_RADIUS*DVec3::NEG_Y,
             radius: GROUND_SPHERE_RADIUS,
+            speed: None,
             material,
         });
     } {
@@ -88,6 +98,7 @@ fn generate_objects(
         scene_config.scene.push(ObjectConfig::Sphere {
             center: SMALL_SPHERE_RADIUS*DVec3::Y,
             radius: SMALL_SPHERE_RADIUS,
+            speed: None,
             material,
         });
     } {
@@ -105,6 +116,7 @@ fn generate_objects(
         scene_config.scene.push(ObjectConfig::Sphere {
             center: 7.0*DVec3::Y,
             radius: 1.0,
+            speed: None,
             material,
         });
     }
@@ -119,7 +131,7 @@ pub fn run(args: &CreateArgs) -> Result<()> {
             look_from: Some(DVec3::new(26.0, 3.0, 6.0)),
             look_at: Some(2.0*DVec3::Y),
             field_of_view: Some(20.),
-            ray_max_bounces: Some(10),
+            ray_max_bounces: Some(64),
             samples_per_pixel: Some(10),
             ..CameraConfig::default()
         })
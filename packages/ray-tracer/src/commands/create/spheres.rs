@@ -25,7 +25,13 @@ fn generate_dielectric(
 
     scene_config.materials.push((
         id.clone(),
-        MaterialConfig::Dielectric { refraction_index: 1.5 },
+        MaterialConfig::Dielectric {
+            refraction_index: 1.5,
+            dispersion: None,
+            roughness: None,
+            absorption_color: None,
+            absorption_distance: None,
+        },
     ));
     id
 }
@@ -53,7 +59,7 @@ fn generate_lambertian(
 fn generate_metal(
     scene_config: &mut SceneConfig,
     color: DVec3,
-    fuzz: f64,
+    roughness: f64,
 ) -> Box<str> {
     let mat_id = get_next_material_id();
     let tex_id = get_next_texture_id();
@@ -65,7 +71,9 @@ fn generate_metal(
     scene_config.materials.push((
         mat_id.clone(),
         MaterialConfig::Metal {
-            fuzz,
+            roughness: Some(roughness),
+            anisotropy: None,
+            conductor: None,
             texture: Some(tex_id),
         },
     ));
@@ -86,6 +94,7 @@ fn generate_objects(scene_config: &mut SceneConfig, seed: u64) {
         scene_config.scene.push(ObjectConfig::Sphere {
             center: GROUND_SPHERE_RADIUS*DVec3::NEG_Y,
             radius: GROUND_SPHERE_RADIUS,
+            speed: None,
             material,
         });
     } {
@@ -93,6 +102,7 @@ fn generate_objects(scene_config: &mut SceneConfig, seed: u64) {
         scene_config.scene.push(ObjectConfig::Sphere {
             center: LARGE_SPHERE_RADIUS*DVec3::Y,
             radius: LARGE_SPHERE_RADIUS,
+            speed: None,
             material,
         });
     } {
@@ -102,16 +112,18 @@ fn generate_objects(scene_config: &mut SceneConfig, seed: u64) {
         scene_config.scene.push(ObjectConfig::Sphere {
             center: LARGE_SPHERE_RADIUS*DVec3::Y - 4.0*DVec3::X,
             radius: LARGE_SPHERE_RADIUS,
+            speed: None,
             material,
         });
     } {
         let color = DVec3::from_rng(&mut rng);
-        let fuzz = rng.random();
-        let material = Some(generate_metal(scene_config, color, fuzz));
+        let roughness = rng.random();
+        let material = Some(generate_metal(scene_config, color, roughness));
 
         scene_config.scene.push(ObjectConfig::Sphere {
             center: LARGE_SPHERE_RADIUS*DVec3::Y + 4.0*DVec3::X,
             radius: LARGE_SPHERE_RADIUS,
+            speed: None,
             material,
         });
     }
@@ -133,17 +145,23 @@ fn generate_objects(scene_config: &mut SceneConfig, seed: u64) {
         scene_config.scene.push(ObjectConfig::Sphere {
             center,
             radius,
+            speed: None,
             material,
         });
     }
 }
 
 pub fn run(args: &CreateArgs) -> Result<()> {
-    let mut scene_config = SceneConfig::default();
+    let mut scene_config = SceneConfig {
+        background: Some(BackgroundConfig::Gradient {
+            horizon: DVec3::ONE,
+            zenith: DVec3::new(0.5, 0.7, 1.0),
+        }),
+        ..SceneConfig::default()
+    };
 
     scene_config.camera
         .merge_with(&CameraConfig {
-            background_color: Some(DVec3::new(0.7, 0.8, 1.0)),
             look_from: Some(13.*DVec3::X + 2.*DVec3::Y + 3.*DVec3::Z),
             look_at: Some(DVec3::ZERO),
             field_of_view: Some(20.),
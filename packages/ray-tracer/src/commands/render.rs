@@ -1,4 +1,5 @@
 use std::fs;
+use std::ops::RangeInclusive;
 use std::path::PathBuf;
 
 use anyhow::Result;
@@ -33,6 +34,15 @@ pub struct Render {
     #[command(flatten)]
     camera: CameraConfig,
 
+    /// Render the given range of frames (e.g. '1..240') of an animated scene,
+    /// each frame is written to the output path suffixed by the frame number.
+    #[arg(
+        long,
+        value_name = "FRAMES",
+        value_parser = parse_frames,
+    )]
+    frames: Option<RangeInclusive<usize>>,
+
     /// Show progress.
     #[arg(short, long)]
     verbose: bool
@@ -47,11 +57,11 @@ impl Verbosity for Render {
 fn render_scene(
     cli: &Render,
     scene: &Scene,
+    prefix: String,
 ) -> Rgb32FImage {
-    let bar = get_progress(cli, "Rendering").map(|bar| {
+    let bar = get_progress(cli, prefix).inspect(|bar| {
         bar.set_position(0);
         bar.set_length(scene.camera.get_image_size().get_pixel_count() as u64);
-        bar
     });
 
     let start = Utc::now();
@@ -102,14 +112,31 @@ fn dump_image(
 }
 
 pub fn run(args: &Render) -> Result<()> {
-    let (mut file, format) = args.image.get_file()?;
+    if let Some(frames) = args.frames.clone() {
+        let mut animation = SceneConfig::try_load_scene(args.scene.as_path())?.try_build_animation()?;
+
+        animation.with_camera_overrides(&args.camera);
+
+        for frame in frames {
+            let (mut file, format) = args.image.get_frame_file(frame)?;
+
+            let scene = animation.try_build_frame(frame)?;
+            let image = render_scene(args, &scene, format!("Frame {frame:04}"));
 
-    let mut scene_config = SceneConfig::try_load_scene(args.scene.as_path())?;
+            dump_image(args, &mut file, image, format)?;
+        }
 
-    scene_config.camera.merge_with(&args.camera);
+        Ok(())
+    } else {
+        let (mut file, format) = args.image.get_file()?;
 
-    let scene = scene_config.try_build()?;
-    let image = render_scene(args, &scene);
+        let mut animation = SceneConfig::try_load_scene(args.scene.as_path())?.try_build_animation()?;
 
-    dump_image(args, &mut file, image, format)
+        animation.with_camera_overrides(&args.camera);
+
+        let scene = animation.try_build_frame(DEFAULT_FRAME)?;
+        let image = render_scene(args, &scene, "Rendering".into());
+
+        dump_image(args, &mut file, image, format)
+    }
 }
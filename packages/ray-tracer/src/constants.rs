@@ -1,5 +1,9 @@
 pub(crate) const DEFAULT_IMAGE_GAMMA_VALUE: f32 = 0.5;
 
-pub(crate) const PROGRESS_TEMPLATE: &'static str = "{prefix:>10} - [{bar:40}] {percent:>3}%";
-pub(crate) const SPINNER_TEMPLATE: &'static str = "{prefix:>10} - {spinner:40}";
-pub(crate) const PROGRESS_TEMPLATE_FINISHED: &'static str = "{prefix:>10} - {msg}";
+pub(crate) const DEFAULT_FISHEYE_ANGLE: f64 = 180.0;
+
+pub(crate) const DEFAULT_FRAME: usize = 1;
+
+pub(crate) const PROGRESS_TEMPLATE: &str = "{prefix:>10} - [{bar:40}] {percent:>3}%";
+pub(crate) const SPINNER_TEMPLATE: &str = "{prefix:>10} - {spinner:40}";
+pub(crate) const PROGRESS_TEMPLATE_FINISHED: &str = "{prefix:>10} - {msg}";
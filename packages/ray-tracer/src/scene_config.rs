@@ -1,6 +1,14 @@
-use std::collections::HashMap;
+use std::collections::{
+    HashMap,
+    HashSet,
+};
 use std::fs;
 use std::ffi::OsStr;
+use std::f64::consts::PI;
+use std::ops::{
+    Add,
+    Mul,
+};
 use std::path::{
     Path,
     PathBuf,
@@ -12,7 +20,11 @@ use anyhow::{
     Result,
 };
 
-use glam::DVec3;
+use glam::{
+    DMat3,
+    DVec2,
+    DVec3,
+};
 
 use nr_ray_tracer_lib::prelude::*;
 
@@ -20,10 +32,61 @@ use serde::{
     Deserialize,
     Serialize,
 };
+use serde_with::skip_serializing_none;
 
 use crate::cli::*;
+use crate::constants::*;
+
+#[derive(Clone, Copy, Debug, Deserialize, Serialize)]
+pub enum ColorSpaceConfig {
+    Srgb,
+    Linear,
+}
+
+impl From<ColorSpaceConfig> for ColorSpace {
+    fn from(config: ColorSpaceConfig) -> Self {
+        match config {
+            ColorSpaceConfig::Srgb => ColorSpace::Srgb,
+            ColorSpaceConfig::Linear => ColorSpace::Linear,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, Serialize)]
+pub enum ImageFilterConfig {
+    Nearest,
+    Bilinear,
+    Trilinear,
+}
+
+impl From<ImageFilterConfig> for Filter {
+    fn from(config: ImageFilterConfig) -> Self {
+        match config {
+            ImageFilterConfig::Nearest => Filter::Nearest,
+            ImageFilterConfig::Bilinear => Filter::Bilinear,
+            ImageFilterConfig::Trilinear => Filter::Trilinear,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, Serialize)]
+pub enum ImageWrapConfig {
+    Repeat,
+    Mirror,
+    Clamp,
+}
+
+impl From<ImageWrapConfig> for Wrap {
+    fn from(config: ImageWrapConfig) -> Self {
+        match config {
+            ImageWrapConfig::Repeat => Wrap::Repeat,
+            ImageWrapConfig::Mirror => Wrap::Mirror,
+            ImageWrapConfig::Clamp => Wrap::Clamp,
+        }
+    }
+}
 
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Clone, Debug, Deserialize, Serialize)]
 pub enum TextureConfig {
     Checker {
         even: Option<Box<str>>,
@@ -31,7 +94,12 @@ pub enum TextureConfig {
         scale: Option<f64>,
     },
     Image {
-        path: PathBuf
+        path: PathBuf,
+        color_space: Option<ColorSpaceConfig>,
+        filter: Option<ImageFilterConfig>,
+        wrap: Option<ImageWrapConfig>,
+        scale: Option<DVec2>,
+        offset: Option<DVec2>,
     },
     Marble {
         seed: Option<u32>,
@@ -50,6 +118,27 @@ pub enum TextureConfig {
 }
 
 impl TextureConfig {
+    // Images holding data (e.g. normal maps or masks) are decoded as
+    // linear.
+    fn try_into_linear(self, id: &str) -> Result<Self> {
+        match self {
+            Self::Image { color_space: Some(ColorSpaceConfig::Srgb), .. } => {
+                Err(anyhow!("texture holding data must be linear: '{id}'"))
+            },
+            Self::Image { path, filter, wrap, scale, offset, .. } => {
+                Ok(Self::Image {
+                    path,
+                    color_space: Some(ColorSpaceConfig::Linear),
+                    filter,
+                    wrap,
+                    scale,
+                    offset,
+                })
+            },
+            _ => Ok(self),
+        }
+    }
+
     pub fn try_make_texture(
         &self,
         textures: &HashMap<Box<str>, Arc<dyn Texture + Send + Sync>>,
@@ -84,8 +173,23 @@ impl TextureConfig {
 
                 Ok(Arc::new(checker_builder.build()))
             },
-            Self::Image { path } => {
-                Ok(Arc::new(Image::try_from_path(path)?))
+            Self::Image {
+                path,
+                color_space,
+                filter,
+                wrap,
+                scale,
+                offset,
+            } => {
+                let mut image_builder = ImageBuilder::default();
+
+                image_builder.with_color_space(color_space.map(ColorSpace::from));
+                image_builder.with_filter(filter.map(Filter::from));
+                image_builder.with_wrap(wrap.map(Wrap::from));
+                image_builder.with_scale(*scale);
+                image_builder.with_offset(*offset);
+
+                Ok(Arc::new(image_builder.try_build(path)?))
             },
             Self::Marble {
                 seed,
@@ -122,13 +226,46 @@ impl TextureConfig {
     }
 }
 
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Clone, Debug, Deserialize, Serialize)]
 pub enum MaterialConfig {
+    BumpMap {
+        material: Box<str>,
+        texture: Box<str>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        strength: Option<f64>,
+    },
+    Cutout {
+        material: Box<str>,
+        texture: Box<str>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        threshold: Option<f64>,
+    },
     Dielectric {
         refraction_index: f64,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        dispersion: Option<DispersionConfig>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        roughness: Option<f64>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        absorption_color: Option<DVec3>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        absorption_distance: Option<f64>,
     },
     DiffuseLight {
-        intensity: f64,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        intensity: Option<f64>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        power: Option<f64>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        temperature: Option<f64>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        two_sided: Option<bool>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        profile: Option<PathBuf>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        profile_axis: Option<DVec3>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        profile_up: Option<DVec3>,
         #[serde(skip_serializing_if = "Option::is_none")]
         texture: Option<Box<str>>,
     },
@@ -137,10 +274,138 @@ pub enum MaterialConfig {
         texture: Option<Box<str>>,
     },
     Metal {
-        fuzz: f64,
+        #[serde(alias = "fuzz", skip_serializing_if = "Option::is_none")]
+        roughness: Option<f64>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        anisotropy: Option<f64>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        conductor: Option<ConductorConfig>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        texture: Option<Box<str>>,
+    },
+    Mix {
+        first: Box<str>,
+        second: Box<str>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        factor: Option<f64>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        mask: Option<Box<str>>,
+    },
+    NormalMap {
+        material: Box<str>,
+        texture: Box<str>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        strength: Option<f64>,
+    },
+    Principled {
+        #[serde(skip_serializing_if = "Option::is_none")]
+        base_color: Option<DVec3>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        base_color_texture: Option<Box<str>>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        metallic: Option<f64>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        metallic_texture: Option<Box<str>>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        roughness: Option<f64>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        roughness_texture: Option<Box<str>>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        specular: Option<f64>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        specular_texture: Option<Box<str>>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        clearcoat: Option<f64>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        clearcoat_texture: Option<Box<str>>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        clearcoat_roughness: Option<f64>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        sheen: Option<f64>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        sheen_texture: Option<Box<str>>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        transmission: Option<f64>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        transmission_texture: Option<Box<str>>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        refraction_index: Option<f64>,
+    },
+    Subsurface {
+        #[serde(skip_serializing_if = "Option::is_none")]
+        albedo: Option<DVec3>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        texture: Option<Box<str>>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        mean_free_path: Option<DVec3>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        refraction_index: Option<f64>,
+    },
+    ThinDielectric {
+        #[serde(skip_serializing_if = "Option::is_none")]
+        refraction_index: Option<f64>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        color: Option<DVec3>,
+    },
+    Translucent {
         #[serde(skip_serializing_if = "Option::is_none")]
         texture: Option<Box<str>>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        back_texture: Option<Box<str>>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        transmission: Option<f64>,
+    },
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, Serialize)]
+pub enum ConductorConfig {
+    Aluminium,
+    Copper,
+    Gold,
+    Silver,
+    Custom {
+        eta: DVec3,
+        k: DVec3,
+    },
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, Serialize)]
+pub enum DispersionConfig {
+    Bk7,
+    DenseFlint,
+    FusedSilica,
+    Cauchy {
+        a: f64,
+        b: f64,
     },
+    Sellmeier {
+        b: [f64; 3],
+        c: [f64; 3],
+    },
+}
+
+impl From<DispersionConfig> for Dispersion {
+    fn from(config: DispersionConfig) -> Self {
+        match config {
+            DispersionConfig::Bk7 => Dispersion::BK7,
+            DispersionConfig::DenseFlint => Dispersion::DENSE_FLINT,
+            DispersionConfig::FusedSilica => Dispersion::FUSED_SILICA,
+            DispersionConfig::Cauchy { a, b } => Dispersion::Cauchy { a, b },
+            DispersionConfig::Sellmeier { b, c } => Dispersion::Sellmeier { b, c },
+        }
+    }
+}
+
+impl From<ConductorConfig> for Conductor {
+    fn from(config: ConductorConfig) -> Self {
+        match config {
+            ConductorConfig::Aluminium => Conductor::ALUMINIUM,
+            ConductorConfig::Copper => Conductor::COPPER,
+            ConductorConfig::Gold => Conductor::GOLD,
+            ConductorConfig::Silver => Conductor::SILVER,
+            ConductorConfig::Custom { eta, k } => Conductor { eta, k },
+        }
+    }
 }
 
 fn get_texture(
@@ -159,22 +424,203 @@ fn get_texture(
     }
 }
 
+fn get_optional_texture(
+    id: &Option<Box<str>>,
+    textures: &HashMap<Box<str>, Arc<dyn Texture + Send + Sync>>,
+) -> Result<Option<Arc<dyn Texture + Send + Sync>>> {
+    id.as_ref()
+        .map(|id| textures
+            .get(id)
+            .cloned()
+            .ok_or(anyhow!("invalid texture id: '{id}'"))
+        )
+        .transpose()
+}
+
 impl MaterialConfig {
+    // Materials wrapped by this one.
+    fn get_wrapped_materials(&self) -> Vec<&str> {
+        match self {
+            Self::BumpMap { material, .. }
+            | Self::Cutout { material, .. }
+            | Self::NormalMap { material, .. } => {
+                vec![material]
+            },
+            Self::Mix { first, second, .. } => {
+                vec![first, second]
+            },
+            _ => Vec::new(),
+        }
+    }
+
+    // Textures holding colors.
+    fn get_color_textures(&self) -> Vec<&str> {
+        match self {
+            Self::DiffuseLight { texture, .. }
+            | Self::Lambertian { texture }
+            | Self::Metal { texture, .. }
+            | Self::Subsurface { texture, .. } => {
+                texture.as_deref().into_iter().collect()
+            },
+            Self::Principled { base_color_texture, .. } => {
+                base_color_texture.as_deref().into_iter().collect()
+            },
+            Self::Translucent { texture, back_texture, .. } => {
+                texture.as_deref().into_iter().chain(back_texture.as_deref()).collect()
+            },
+            _ => Vec::new(),
+        }
+    }
+
+    // Textures holding data rather than colors (e.g. masks, height maps or
+    // parameters read from their luminance).
+    fn get_data_textures(&self) -> Vec<&str> {
+        match self {
+            Self::BumpMap { texture, .. }
+            | Self::Cutout { texture, .. }
+            | Self::NormalMap { texture, .. } => {
+                vec![texture]
+            },
+            Self::Mix { mask, .. } => {
+                mask.as_deref().into_iter().collect()
+            },
+            Self::Principled {
+                metallic_texture,
+                roughness_texture,
+                specular_texture,
+                clearcoat_texture,
+                sheen_texture,
+                transmission_texture,
+                ..
+            } => {
+                [
+                    metallic_texture,
+                    roughness_texture,
+                    specular_texture,
+                    clearcoat_texture,
+                    sheen_texture,
+                    transmission_texture,
+                ].into_iter().filter_map(Option::as_deref).collect()
+            },
+            _ => Vec::new(),
+        }
+    }
+
     pub fn try_make_material(
         &self,
         textures: &HashMap<Box<str>, Arc<dyn Texture + Send + Sync>>,
         texture_fallback: Arc<dyn Texture + Send + Sync>,
+        materials: &HashMap<Box<str>, Arc<dyn Material + Send + Sync>>,
     ) -> Result<Arc<dyn Material + Send + Sync>> {
         match self {
-            Self::Dielectric {refraction_index } => {
-                Ok(Arc::new(Dielectric::new(*refraction_index)))
+            Self::BumpMap {
+                material,
+                texture,
+                strength,
+            } => {
+                let mut bump_map_builder = BumpMapBuilder::default();
+
+                bump_map_builder.with_material(Some(
+                    materials
+                        .get(material)
+                        .ok_or(anyhow!("invalid material id: '{material}'"))?
+                        .clone()
+                ));
+                bump_map_builder.with_texture(Some(
+                    textures
+                        .get(texture)
+                        .ok_or(anyhow!("invalid texture id: '{texture}'"))?
+                        .clone()
+                ));
+                bump_map_builder.with_strength(*strength);
+
+                Ok(Arc::new(bump_map_builder.build()))
+            },
+            Self::Cutout {
+                material,
+                texture,
+                threshold,
+            } => {
+                let mut cutout_builder = CutoutBuilder::default();
+
+                cutout_builder.with_material(Some(
+                    materials
+                        .get(material)
+                        .ok_or(anyhow!("invalid material id: '{material}'"))?
+                        .clone()
+                ));
+                cutout_builder.with_texture(Some(
+                    textures
+                        .get(texture)
+                        .ok_or(anyhow!("invalid texture id: '{texture}'"))?
+                        .clone()
+                ));
+                cutout_builder.with_threshold(*threshold);
+
+                Ok(Arc::new(cutout_builder.build()))
             },
-            Self::DiffuseLight { intensity, texture } => {
+            Self::Dielectric {
+                refraction_index,
+                dispersion,
+                roughness,
+                absorption_color,
+                absorption_distance,
+            } => {
+                let mut dielectric_builder = DielectricBuilder::default();
+
+                dielectric_builder.with_refraction_index(Some(*refraction_index));
+                dielectric_builder.with_dispersion(dispersion.map(Dispersion::from));
+                dielectric_builder.with_roughness(*roughness);
+                dielectric_builder.with_absorption_color(*absorption_color);
+                dielectric_builder.with_absorption_distance(*absorption_distance);
+
+                Ok(Arc::new(dielectric_builder.build()))
+            },
+            Self::DiffuseLight {
+                intensity,
+                power,
+                temperature,
+                two_sided,
+                profile,
+                profile_axis,
+                profile_up,
+                texture,
+            } => {
                 let mut diffuse_light_builder = DiffuseLightBuilder::default();
-                let texture = get_texture(texture, textures, texture_fallback)?;
 
-                diffuse_light_builder.with_intensity(*intensity);
-                diffuse_light_builder.with_texture(texture);
+                // A temperature tints the texture, which is white unless
+                // given.
+                if let Some(temperature) = temperature {
+                    diffuse_light_builder.with_temperature(*temperature);
+                }
+
+                if texture.is_some() || temperature.is_none() {
+                    diffuse_light_builder.with_texture(get_texture(texture, textures, texture_fallback)?);
+                }
+
+                if let Some(intensity) = intensity {
+                    diffuse_light_builder.with_intensity(*intensity);
+                }
+
+                if let Some(power) = power {
+                    diffuse_light_builder.with_power(*power);
+                }
+
+                if let Some(two_sided) = two_sided {
+                    diffuse_light_builder.with_two_sided(*two_sided);
+                }
+
+                if let Some(profile) = profile {
+                    diffuse_light_builder.with_profile(Arc::new(IesProfile::try_from_path(profile)?));
+                }
+
+                if let Some(profile_axis) = profile_axis {
+                    diffuse_light_builder.with_profile_axis(*profile_axis);
+                }
+
+                if let Some(profile_up) = profile_up {
+                    diffuse_light_builder.with_profile_up(*profile_up);
+                }
 
                 Ok(Arc::new(diffuse_light_builder.build()))
             },
@@ -184,20 +630,453 @@ impl MaterialConfig {
 
                 Ok(Arc::new(lambertian))
             },
-            Self::Metal { texture, fuzz } => {
-                let texture = get_texture(texture, textures, texture_fallback)?;
+            Self::Metal {
+                roughness,
+                anisotropy,
+                conductor,
+                texture,
+            } => {
                 let mut metal_builder = MetalBuilder::default();
 
-                metal_builder.with_texture(Some(texture));
-                metal_builder.with_fuzz(Some(*fuzz));
+                // Conductors are not tinted unless a texture is given.
+                if texture.is_some() || conductor.is_none() {
+                    metal_builder.with_texture(Some(get_texture(texture, textures, texture_fallback)?));
+                }
+
+                metal_builder.with_roughness(*roughness);
+                metal_builder.with_anisotropy(*anisotropy);
+                metal_builder.with_conductor(conductor.map(Conductor::from));
 
                 Ok(Arc::new(metal_builder.build()))
             },
+            Self::Mix {
+                first,
+                second,
+                factor,
+                mask,
+            } => {
+                let mut mix_builder = MixBuilder::default();
+
+                mix_builder.with_first(Some(
+                    materials
+                        .get(first)
+                        .ok_or(anyhow!("invalid material id: '{first}'"))?
+                        .clone()
+                ));
+                mix_builder.with_second(Some(
+                    materials
+                        .get(second)
+                        .ok_or(anyhow!("invalid material id: '{second}'"))?
+                        .clone()
+                ));
+                mix_builder.with_factor(*factor);
+                mix_builder.with_mask(get_optional_texture(mask, textures)?);
+
+                Ok(Arc::new(mix_builder.build()))
+            },
+            Self::NormalMap {
+                material,
+                texture,
+                strength,
+            } => {
+                let mut normal_map_builder = NormalMapBuilder::default();
+
+                normal_map_builder.with_material(Some(
+                    materials
+                        .get(material)
+                        .ok_or(anyhow!("invalid material id: '{material}'"))?
+                        .clone()
+                ));
+                normal_map_builder.with_texture(Some(
+                    textures
+                        .get(texture)
+                        .ok_or(anyhow!("invalid texture id: '{texture}'"))?
+                        .clone()
+                ));
+                normal_map_builder.with_strength(*strength);
+
+                Ok(Arc::new(normal_map_builder.build()))
+            },
+            Self::Principled {
+                base_color,
+                base_color_texture,
+                metallic,
+                metallic_texture,
+                roughness,
+                roughness_texture,
+                specular,
+                specular_texture,
+                clearcoat,
+                clearcoat_texture,
+                clearcoat_roughness,
+                sheen,
+                sheen_texture,
+                transmission,
+                transmission_texture,
+                refraction_index,
+            } => {
+                let mut principled_builder = PrincipledBuilder::default();
+
+                principled_builder.with_base_color(*base_color);
+                principled_builder.with_base_color_texture(get_optional_texture(base_color_texture, textures)?);
+                principled_builder.with_metallic(*metallic);
+                principled_builder.with_metallic_texture(get_optional_texture(metallic_texture, textures)?);
+                principled_builder.with_roughness(*roughness);
+                principled_builder.with_roughness_texture(get_optional_texture(roughness_texture, textures)?);
+                principled_builder.with_specular(*specular);
+                principled_builder.with_specular_texture(get_optional_texture(specular_texture, textures)?);
+                principled_builder.with_clearcoat(*clearcoat);
+                principled_builder.with_clearcoat_texture(get_optional_texture(clearcoat_texture, textures)?);
+                principled_builder.with_clearcoat_roughness(*clearcoat_roughness);
+                principled_builder.with_sheen(*sheen);
+                principled_builder.with_sheen_texture(get_optional_texture(sheen_texture, textures)?);
+                principled_builder.with_transmission(*transmission);
+                principled_builder.with_transmission_texture(get_optional_texture(transmission_texture, textures)?);
+                principled_builder.with_refraction_index(*refraction_index);
+
+                Ok(Arc::new(principled_builder.build()))
+            },
+            Self::Subsurface {
+                albedo,
+                texture,
+                mean_free_path,
+                refraction_index,
+            } => {
+                let mut subsurface_builder = SubsurfaceBuilder::default();
+
+                if texture.is_some() || albedo.is_none() {
+                    subsurface_builder.with_texture(Some(get_texture(texture, textures, texture_fallback)?));
+                } else {
+                    subsurface_builder.with_albedo(*albedo);
+                }
+                subsurface_builder.with_mean_free_path(*mean_free_path);
+                subsurface_builder.with_refraction_index(*refraction_index);
+
+                Ok(Arc::new(subsurface_builder.build()))
+            },
+            Self::ThinDielectric {
+                refraction_index,
+                color,
+            } => {
+                let mut thin_dielectric_builder = ThinDielectricBuilder::default();
+
+                thin_dielectric_builder.with_refraction_index(*refraction_index);
+                thin_dielectric_builder.with_color(*color);
+
+                Ok(Arc::new(thin_dielectric_builder.build()))
+            },
+            Self::Translucent {
+                texture,
+                back_texture,
+                transmission,
+            } => {
+                let mut translucent_builder = TranslucentBuilder::default();
+
+                translucent_builder.with_texture(Some(get_texture(texture, textures, texture_fallback)?));
+                translucent_builder.with_back_texture(get_optional_texture(back_texture, textures)?);
+                translucent_builder.with_transmission(*transmission);
+
+                Ok(Arc::new(translucent_builder.build()))
+            },
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub enum BackgroundConfig {
+    Color {
+        color: DVec3,
+    },
+    EnvironmentMap {
+        path: PathBuf,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        rotation: Option<f64>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        intensity: Option<f64>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        importance_sampling: Option<bool>,
+    },
+    Sky {
+        #[serde(skip_serializing_if = "Option::is_none")]
+        sun_direction: Option<DVec3>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        turbidity: Option<f64>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        intensity: Option<f64>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        sun_intensity: Option<f64>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        sun_size: Option<f64>,
+    },
+    Gradient {
+        horizon: DVec3,
+        zenith: DVec3,
+    },
+    Texture {
+        texture: Box<str>,
+    },
+}
+
+impl BackgroundConfig {
+    fn get_color_texture(&self) -> Option<&str> {
+        match self {
+            Self::Texture { texture } => Some(texture),
+            _ => None,
+        }
+    }
+
+    pub fn try_make_background(
+        &self,
+        textures: &HashMap<Box<str>, Arc<dyn Texture + Send + Sync>>,
+    ) -> Result<Arc<dyn Background + Send + Sync>> {
+        match self {
+            Self::Color { color } => {
+                Ok(Arc::new(ConstantBackground::new(*color)))
+            },
+            Self::EnvironmentMap {
+                path,
+                rotation,
+                intensity,
+                importance_sampling,
+            } => {
+                let mut environment_map_builder = EnvironmentMapBuilder::default();
+
+                environment_map_builder.with_rotation(*rotation);
+                environment_map_builder.with_intensity(*intensity);
+                environment_map_builder.with_importance_sampling(*importance_sampling);
+
+                Ok(Arc::new(environment_map_builder.build(Image::try_from_path(path)?)))
+            },
+            Self::Sky {
+                sun_direction,
+                turbidity,
+                intensity,
+                sun_intensity,
+                sun_size,
+            } => {
+                let mut sky_builder = PhysicalSkyBuilder::default();
+
+                sky_builder.with_sun_direction(*sun_direction);
+                sky_builder.with_turbidity(*turbidity);
+                sky_builder.with_intensity(*intensity);
+                sky_builder.with_sun_intensity(*sun_intensity);
+                sky_builder.with_sun_size(*sun_size);
+
+                Ok(Arc::new(sky_builder.build()))
+            },
+            Self::Gradient {
+                horizon,
+                zenith,
+            } => {
+                Ok(Arc::new(GradientBackground::new(*horizon, *zenith)))
+            },
+            Self::Texture { texture } => {
+                let texture = textures
+                    .get(texture)
+                    .ok_or(anyhow!("invalid texture index"))?
+                    .clone();
+
+                Ok(Arc::new(TextureBackground::new(texture)))
+            },
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub enum LightConfig {
+    Directional {
+        direction: DVec3,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        color: Option<DVec3>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        intensity: Option<f64>,
+    },
+    Point {
+        position: DVec3,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        color: Option<DVec3>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        intensity: Option<f64>,
+    },
+    Spot {
+        position: DVec3,
+        look_at: DVec3,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        color: Option<DVec3>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        intensity: Option<f64>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        cone_angle: Option<f64>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        falloff: Option<f64>,
+    },
+}
+
+impl LightConfig {
+    pub fn make_light(&self) -> Arc<dyn Light + Send + Sync> {
+        match self {
+            Self::Directional {
+                direction,
+                color,
+                intensity,
+            } => {
+                let mut directional_light_builder = DirectionalLightBuilder::default();
+
+                directional_light_builder.with_direction(Some(*direction));
+                directional_light_builder.with_color(*color);
+                directional_light_builder.with_intensity(*intensity);
+
+                Arc::new(directional_light_builder.build())
+            },
+            Self::Point {
+                position,
+                color,
+                intensity,
+            } => {
+                let mut point_light_builder = PointLightBuilder::default();
+
+                point_light_builder.with_position(Some(*position));
+                point_light_builder.with_color(*color);
+                point_light_builder.with_intensity(*intensity);
+
+                Arc::new(point_light_builder.build())
+            },
+            Self::Spot {
+                position,
+                look_at,
+                color,
+                intensity,
+                cone_angle,
+                falloff,
+            } => {
+                let mut spot_light_builder = SpotLightBuilder::default();
+
+                spot_light_builder.with_position(Some(*position));
+                spot_light_builder.with_look_at(Some(*look_at));
+                spot_light_builder.with_color(*color);
+                spot_light_builder.with_intensity(*intensity);
+                spot_light_builder.with_cone_angle(*cone_angle);
+                spot_light_builder.with_falloff(*falloff);
+
+                Arc::new(spot_light_builder.build())
+            },
+        }
+    }
+}
+
+fn interpolate<K, T>(
+    keyframes: &[K],
+    frame: f64,
+    get_frame: impl Fn(&K) -> f64,
+    get_value: impl Fn(&K) -> Option<T>,
+) -> Option<T> where T: Copy + Add<Output = T> + Mul<f64, Output = T> {
+    let mut values = keyframes
+        .iter()
+        .filter_map(|keyframe| get_value(keyframe).map(|value| (get_frame(keyframe), value)))
+        .collect::<Vec<_>>();
+
+    values.sort_by(|(f1, _), (f2, _)| f64::total_cmp(f1, f2));
+
+    let next = values.iter().position(|(f, _)| *f > frame);
+
+    match next {
+        Some(0) => values.first().map(|(_, value)| *value),
+        Some(i) => {
+            let (f0, v0) = values[i - 1];
+            let (f1, v1) = values[i];
+            let k = (frame - f0)/(f1 - f0);
+
+            Some(v0*(1.0 - k) + v1*k)
+        },
+        None => values.last().map(|(_, value)| *value),
+    }
+}
+
+#[skip_serializing_none]
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct CameraKeyframe {
+    pub frame: f64,
+    pub look_at: Option<DVec3>,
+    pub look_from: Option<DVec3>,
+    pub field_of_view: Option<f64>,
+    pub focus_distance: Option<f64>,
+}
+
+impl CameraKeyframe {
+    fn get_camera_config(
+        keyframes: &[Self],
+        frame: f64,
+    ) -> CameraConfig {
+        let get_frame = |keyframe: &Self| keyframe.frame;
+
+        CameraConfig {
+            look_at: interpolate(keyframes, frame, get_frame, |keyframe| keyframe.look_at),
+            look_from: interpolate(keyframes, frame, get_frame, |keyframe| keyframe.look_from),
+            field_of_view: interpolate(keyframes, frame, get_frame, |keyframe| keyframe.field_of_view),
+            focus_distance: interpolate(keyframes, frame, get_frame, |keyframe| keyframe.focus_distance),
+            ..CameraConfig::default()
+        }
+    }
+}
+
+#[skip_serializing_none]
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct TransformKeyframe {
+    pub frame: f64,
+    pub scale: Option<DVec3>,
+    pub rotate: Option<DVec3>,
+    pub translate: Option<DVec3>,
+}
+
+impl TransformKeyframe {
+    // Scale and rotation applied at the given frame.
+    fn get_linear_transform(
+        keyframes: &[Self],
+        frame: f64,
+    ) -> DMat3 {
+        let get_frame = |keyframe: &Self| keyframe.frame;
+        let mut transform = DMat3::IDENTITY;
+
+        if let Some(scale) = interpolate(keyframes, frame, get_frame, |keyframe| keyframe.scale) {
+            transform = DMat3::from_diagonal(scale);
+        }
+
+        if let Some(rotate) = interpolate(keyframes, frame, get_frame, |keyframe| keyframe.rotate) {
+            transform = DMat3::from_rotation_z(rotate.z)
+                *DMat3::from_rotation_y(rotate.y)
+                *DMat3::from_rotation_x(rotate.x)
+                *transform;
         }
+
+        transform
+    }
+
+    fn apply(
+        keyframes: &[Self],
+        frame: f64,
+        mut object: Arc<dyn Hitable + Send + Sync>,
+    ) -> Arc<dyn Hitable + Send + Sync> {
+        let get_frame = |keyframe: &Self| keyframe.frame;
+
+        if let Some(scale) = interpolate(keyframes, frame, get_frame, |keyframe| keyframe.scale) {
+            object = Arc::new(Scale::new(object, scale));
+        }
+
+        if let Some(rotate) = interpolate(keyframes, frame, get_frame, |keyframe| keyframe.rotate) {
+            object = Arc::new(Rotate::axis_x(object, rotate.x));
+            object = Arc::new(Rotate::axis_y(object, rotate.y));
+            object = Arc::new(Rotate::axis_z(object, rotate.z));
+        }
+
+        if let Some(translate) = interpolate(keyframes, frame, get_frame, |keyframe| keyframe.translate) {
+            object = Arc::new(Translate::new(object, translate));
+        }
+
+        object
     }
 }
 
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Clone, Debug, Deserialize, Serialize)]
 pub enum ObjectConfig {
     Quad {
         point: DVec3,
@@ -217,6 +1096,8 @@ pub enum ObjectConfig {
         center: DVec3,
         radius: f64,
         #[serde(skip_serializing_if = "Option::is_none")]
+        speed: Option<DVec3>,
+        #[serde(skip_serializing_if = "Option::is_none")]
         material: Option<Box<str>>,
     },
     Group {
@@ -228,6 +1109,9 @@ pub enum ObjectConfig {
         path: PathBuf,
         #[serde(skip_serializing_if = "Option::is_none")]
         material: Option<Box<str>>,
+        // Loaded once before the including scene is built.
+        #[serde(skip)]
+        scene: Option<Box<SceneConfig>>,
     },
     Ref {
         id: Box<str>,
@@ -256,6 +1140,17 @@ pub enum ObjectConfig {
         offset: DVec3,
         object: Box<ObjectConfig>,
     },
+    Motion {
+        #[serde(skip_serializing_if = "Option::is_none")]
+        velocity: Option<DVec3>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        angular_velocity: Option<DVec3>,
+        object: Box<ObjectConfig>,
+    },
+    Animate {
+        keyframes: Vec<TransformKeyframe>,
+        object: Box<ObjectConfig>,
+    },
 }
 
 fn get_material(
@@ -275,11 +1170,199 @@ fn get_material(
 }
 
 impl ObjectConfig {
+    pub fn is_animated(&self) -> bool {
+        match self {
+            Self::Animate { .. } => true,
+            Self::Group { objects, .. } => {
+                objects.iter().any(Self::is_animated)
+            },
+            Self::RotateX { object, .. }
+            | Self::RotateY { object, .. }
+            | Self::RotateZ { object, .. }
+            | Self::ScaleU { object, .. }
+            | Self::ScaleV { object, .. }
+            | Self::Translate { object, .. }
+            | Self::Motion { object, .. } => {
+                object.is_animated()
+            },
+            // Included scenes are rebuilt at each frame when they are
+            // animated.
+            Self::Scene { scene, .. } => {
+                scene
+                    .as_ref()
+                    .is_some_and(|scene| scene.scene.iter().any(Self::is_animated))
+            },
+            _ => false,
+        }
+    }
+
+    fn try_resolve_includes(&mut self) -> Result<()> {
+        match self {
+            Self::Scene { path, scene, .. } => {
+                if scene.is_none() {
+                    let mut scene_config = SceneConfig::try_load_scene(&*path)?;
+
+                    scene_config.try_resolve_includes()?;
+                    scene.replace(Box::new(scene_config));
+                }
+
+                Ok(())
+            },
+            Self::Group { objects, .. } => {
+                for object_config in objects {
+                    object_config.try_resolve_includes()?;
+                }
+
+                Ok(())
+            },
+            Self::RotateX { object, .. }
+            | Self::RotateY { object, .. }
+            | Self::RotateZ { object, .. }
+            | Self::ScaleU { object, .. }
+            | Self::ScaleV { object, .. }
+            | Self::Translate { object, .. }
+            | Self::Motion { object, .. }
+            | Self::Animate { object, .. } => {
+                object.try_resolve_includes()
+            },
+            _ => Ok(()),
+        }
+    }
+
+    // Point, spot and directional lights of the included scenes, they are
+    // not objects and cannot follow the transforms applied to the include.
+    // Instances only bring their lights once, when they are referenced.
+    fn try_get_included_lights(
+        &self,
+        instances: &HashMap<&str, &ObjectConfig>,
+        transformed: bool,
+        referenced: &mut HashSet<(Box<str>, bool)>,
+    ) -> Result<Vec<LightConfig>> {
+        match self {
+            Self::Scene { path, scene, .. } => {
+                let lights = match scene {
+                    Some(scene) => scene.try_get_lights(transformed)?,
+                    None => Vec::new(),
+                };
+
+                if transformed && !lights.is_empty() {
+                    return Err(anyhow!("lights of an included scene cannot be transformed: '{}'", path.display()));
+                }
+
+                Ok(lights)
+            },
+            Self::Ref { id } => {
+                if !referenced.insert((id.clone(), transformed)) {
+                    return Ok(Vec::new());
+                }
+
+                instances
+                    .get(id.as_ref())
+                    .ok_or(anyhow!("invalid object id: '{id}'"))?
+                    .try_get_included_lights(instances, transformed, referenced)
+            },
+            Self::Group { objects, .. } => {
+                let mut lights = Vec::new();
+
+                for object_config in objects {
+                    lights.extend(object_config.try_get_included_lights(instances, transformed, referenced)?);
+                }
+
+                Ok(lights)
+            },
+            Self::RotateX { object, .. }
+            | Self::RotateY { object, .. }
+            | Self::RotateZ { object, .. }
+            | Self::ScaleU { object, .. }
+            | Self::ScaleV { object, .. }
+            | Self::Translate { object, .. }
+            | Self::Motion { object, .. }
+            | Self::Animate { object, .. } => {
+                object.try_get_included_lights(instances, true, referenced)
+            },
+            _ => Ok(Vec::new()),
+        }
+    }
+
+    // Total area of the surfaces of the object using each material, the
+    // material inherited from the parent object is keyed by None. Non
+    // uniformly scaled spheres count as the sphere of the same volume.
+    // References count the area of their instance each time, instances only
+    // see the ones declared before them.
+    fn add_material_areas(
+        &self,
+        instances: &[(Box<str>, ObjectConfig)],
+        material_id: &Option<Box<str>>,
+        transform: DMat3,
+        frame: f64,
+        areas: &mut HashMap<Option<Box<str>>, f64>,
+    ) {
+        let mut add_area = |material: &Option<Box<str>>, area: f64| {
+            *areas.entry(material.clone().or(material_id.clone())).or_default() += area;
+        };
+
+        match self {
+            Self::Quad { u, v, material, .. } => {
+                add_area(material, (transform*(*u)).cross(transform*(*v)).length());
+            },
+            Self::Triangle { u, v, material, .. } => {
+                add_area(material, 0.5*(transform*(*u)).cross(transform*(*v)).length());
+            },
+            Self::Sphere { radius, material, .. } => {
+                add_area(material, 4.0*PI*radius*radius*transform.determinant().abs().powf(2.0/3.0));
+            },
+            Self::Group { objects, material } => {
+                let material_id = material.clone().or(material_id.clone());
+
+                for object_config in objects {
+                    object_config.add_material_areas(instances, &material_id, transform, frame, areas);
+                }
+            },
+            // Instances are built with the material fallback of the scene.
+            Self::Ref { id } => {
+                if let Some(index) = instances.iter().position(|(instance_id, _)| instance_id == id) {
+                    instances[index].1.add_material_areas(&instances[..index], &None, transform, frame, areas);
+                }
+            },
+            Self::RotateX { object, angle } => {
+                object.add_material_areas(instances, material_id, transform*DMat3::from_rotation_x(*angle), frame, areas);
+            },
+            Self::RotateY { object, angle } => {
+                object.add_material_areas(instances, material_id, transform*DMat3::from_rotation_y(*angle), frame, areas);
+            },
+            Self::RotateZ { object, angle } => {
+                object.add_material_areas(instances, material_id, transform*DMat3::from_rotation_z(*angle), frame, areas);
+            },
+            Self::ScaleU { object, factor } => {
+                object.add_material_areas(instances, material_id, transform*(*factor), frame, areas);
+            },
+            Self::ScaleV { object, scale } => {
+                object.add_material_areas(instances, material_id, transform*DMat3::from_diagonal(*scale), frame, areas);
+            },
+            Self::Translate { object, .. } | Self::Motion { object, .. } => {
+                object.add_material_areas(instances, material_id, transform, frame, areas);
+            },
+            Self::Animate { object, keyframes } => {
+                object.add_material_areas(
+                    instances,
+                    material_id,
+                    transform*TransformKeyframe::get_linear_transform(keyframes, frame),
+                    frame,
+                    areas,
+                );
+            },
+            // Included scenes spread the power of their lights over their
+            // own surfaces.
+            Self::Scene { .. } => {},
+        }
+    }
+
     pub fn try_make_object(
         &self,
         instances: &HashMap<Box<str>, Arc<dyn Hitable + Send + Sync>>,
         materials: &HashMap<Box<str>, Arc<dyn Material + Send + Sync>>,
         material_fallback: Arc<dyn Material + Send + Sync>,
+        frame: f64,
     ) -> Result<Arc<dyn Hitable + Send + Sync>> {
         match self {
             Self::Quad { point, u, v, material } => {
@@ -306,7 +1389,7 @@ impl ObjectConfig {
 
                 Ok(Arc::new(plane_builder.build()))
             },
-            Self::Sphere { center, radius, material } => {
+            Self::Sphere { center, radius, speed, material } => {
                 let material = get_material(material, materials, material_fallback)?;
                 let mut sphere_builder = SphereBuilder::default();
 
@@ -314,6 +1397,10 @@ impl ObjectConfig {
                 sphere_builder.with_radius(*radius);
                 sphere_builder.with_material(material);
 
+                if let Some(speed) = speed {
+                    sphere_builder.with_speed(*speed);
+                }
+
                 Ok(Arc::new(sphere_builder.build()))
             },
             Self::Group { objects, material } => {
@@ -325,6 +1412,7 @@ impl ObjectConfig {
                         instances,
                         materials,
                         material.clone(),
+                        frame,
                     )?;
 
                     group.push(object);
@@ -332,9 +1420,16 @@ impl ObjectConfig {
 
                 Ok(Arc::new(BVH::from(group.as_mut_slice())))
             },
-            Self::Scene { path, material } => {
+            Self::Scene { path, material, scene } => {
                 let material = get_material(material, materials, material_fallback)?;
-                let scene = SceneConfig::try_load_scene(path)?.try_build_aux(Some(material))?;
+                let scene =
+                    scene
+                        .as_ref()
+                        .ok_or(anyhow!("included scene is not loaded: '{}'", path.display()))?
+                        .as_ref()
+                        .clone()
+                        .try_build_aux(Some(material))?
+                        .try_build_frame(frame as usize)?;
 
                 Ok(Arc::new(scene.objects))
             },
@@ -347,43 +1442,64 @@ impl ObjectConfig {
                 Ok(object)
             },
             Self::RotateX { object, angle } => {
-                let object = object.try_make_object(instances, materials, material_fallback)?;
+                let object = object.try_make_object(instances, materials, material_fallback, frame)?;
 
                 Ok(Arc::new(Rotate::axis_x(object, *angle)))
             },
             Self::RotateY { object, angle } => {
-                let object = object.try_make_object(instances, materials, material_fallback)?;
+                let object = object.try_make_object(instances, materials, material_fallback, frame)?;
 
                 Ok(Arc::new(Rotate::axis_y(object, *angle)))
             },
             Self::RotateZ { object, angle } => {
-                let object = object.try_make_object(instances, materials, material_fallback)?;
+                let object = object.try_make_object(instances, materials, material_fallback, frame)?;
 
                 Ok(Arc::new(Rotate::axis_z(object, *angle)))
             },
             Self::ScaleU { object, factor } => {
-                let object = object.try_make_object(instances, materials, material_fallback)?;
+                let object = object.try_make_object(instances, materials, material_fallback, frame)?;
 
                 Ok(Arc::new(Scale::uniform(object, *factor)))
             },
             Self::ScaleV { object, scale } => {
-                let object = object.try_make_object(instances, materials, material_fallback)?;
+                let object = object.try_make_object(instances, materials, material_fallback, frame)?;
 
                 Ok(Arc::new(Scale::new(object, *scale)))
             },
             Self::Translate { object, offset } => {
-                let object = object.try_make_object(instances, materials, material_fallback)?;
+                let object = object.try_make_object(instances, materials, material_fallback, frame)?;
 
                 Ok(Arc::new(Translate::new(object, *offset)))
             },
+            Self::Motion { object, velocity, angular_velocity } => {
+                let object = object.try_make_object(instances, materials, material_fallback, frame)?;
+                let velocity = velocity.unwrap_or(DVec3::ZERO);
+                let angular_velocity = angular_velocity.unwrap_or(DVec3::ZERO);
+
+                Ok(Arc::new(Motion::new(object, velocity, angular_velocity)))
+            },
+            Self::Animate { object, keyframes } => {
+                let object = object.try_make_object(instances, materials, material_fallback, frame)?;
+
+                Ok(TransformKeyframe::apply(keyframes, frame, object))
+            },
         }
     }
 }
 
-#[derive(Debug, Default, Deserialize, Serialize)]
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
 pub struct SceneConfig {
     pub camera: CameraConfig,
 
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub camera_keyframes: Vec<CameraKeyframe>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub background: Option<BackgroundConfig>,
+
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub lights: Vec<LightConfig>,
+
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub textures: Vec<(Box<str>, TextureConfig)>,
 
@@ -407,13 +1523,138 @@ type TextureMap = HashMap<Box<str>, Arc<dyn Texture + Send + Sync>>;
 type MaterialMap = HashMap<Box<str>, Arc<dyn Material + Send + Sync>>;
 type InstanceMap = HashMap<Box<str>, Arc<dyn Hitable + Send + Sync>>;
 
+pub struct SceneAnimation {
+    camera: CameraConfig,
+    camera_keyframes: Vec<CameraKeyframe>,
+    camera_overrides: CameraConfig,
+    background: Option<Arc<dyn Background + Send + Sync>>,
+    lights: Vec<Arc<dyn Light + Send + Sync>>,
+    instances: InstanceMap,
+    materials: MaterialMap,
+    material_fallback: Arc<dyn Material + Send + Sync>,
+    static_objects: BVH,
+    animated_objects: Vec<ObjectConfig>,
+}
+
+impl SceneAnimation {
+    pub fn with_camera_overrides(
+        &mut self,
+        camera: &CameraConfig,
+    ) -> &mut Self {
+        self.camera_overrides.merge_with(camera);
+        self
+    }
+
+    pub fn try_build_frame(
+        &self,
+        frame: usize,
+    ) -> Result<Scene> {
+        let frame = frame as f64;
+
+        // Static objects BVH is shared by all frames, only animated objects
+        // are rebuilt.
+        let objects = if self.animated_objects.is_empty() {
+            self.static_objects.clone()
+        } else {
+            let mut objects: Vec<Arc<dyn Hitable + Send + Sync>> = vec![
+                Arc::new(self.static_objects.clone()),
+            ];
+
+            for object_config in self.animated_objects.iter() {
+                let object = object_config.try_make_object(
+                    &self.instances,
+                    &self.materials,
+                    self.material_fallback.clone(),
+                    frame,
+                )?;
+                objects.push(object);
+            }
+
+            BVH::from(objects.as_mut_slice())
+        };
+
+        let mut camera_config = self.camera.clone();
+
+        camera_config
+            .merge_with(&CameraKeyframe::get_camera_config(&self.camera_keyframes, frame))
+            .merge_with(&self.camera_overrides);
+
+        let mut camera_builder = CameraBuilder::default();
+
+        camera_config.try_update(&mut camera_builder)?;
+
+        // A background color given on the command line takes precedence
+        // over the scene background.
+        if let Some(background) = self.background.as_ref()
+            && self.camera_overrides.background_color.is_none() {
+            camera_builder.with_background(background.clone());
+        }
+
+        if let Some(FocusOn::Auto(pixel)) = camera_config.focus_on {
+            camera_builder.autofocus(&objects, pixel)?;
+        }
+
+        let camera = camera_builder.try_build()?;
+
+        Ok(Scene {
+            camera,
+            objects,
+            lights: self.lights.clone(),
+        })
+    }
+}
+
 impl SceneConfig {
     fn try_build_aux(
         self,
         material_fallback: Option<Arc<dyn Material + Send + Sync>>,
-    ) -> Result<Scene> {
+    ) -> Result<SceneAnimation> {
+        let light_configs = self.try_get_lights(false)?;
+
+        // Textures referenced by a checker are used the same way as the
+        // checker, references always point to previous textures.
+        let mut color_texture_ids = self.materials
+            .iter()
+            .map(|(_, material_config)| material_config)
+            .chain(self.material_fallback.iter())
+            .flat_map(MaterialConfig::get_color_textures)
+            .chain(self.background.iter().filter_map(BackgroundConfig::get_color_texture))
+            .map(Box::from)
+            .collect::<HashSet<Box<str>>>();
+        let mut data_texture_ids = self.materials
+            .iter()
+            .map(|(_, material_config)| material_config)
+            .chain(self.material_fallback.iter())
+            .flat_map(MaterialConfig::get_data_textures)
+            .map(Box::from)
+            .collect::<HashSet<Box<str>>>();
+
+        if let Some(TextureConfig::Checker { even, odd, .. }) = self.texture_fallback.as_ref() {
+            color_texture_ids.extend(even.iter().chain(odd.iter()).cloned());
+        }
+
+        for (texture_id, texture_config) in self.textures.iter().rev() {
+            if let TextureConfig::Checker { even, odd, .. } = texture_config {
+                if color_texture_ids.contains(texture_id) {
+                    color_texture_ids.extend(even.iter().chain(odd.iter()).cloned());
+                }
+                if data_texture_ids.contains(texture_id) {
+                    data_texture_ids.extend(even.iter().chain(odd.iter()).cloned());
+                }
+            }
+        }
+
+        if let Some(texture_id) = color_texture_ids.intersection(&data_texture_ids).next() {
+            return Err(anyhow!("texture used both as color and as data: '{texture_id}'"));
+        }
+
         let mut textures = TextureMap::new();
         for (texture_id, texture_config) in self.textures {
+            let texture_config = if data_texture_ids.contains(&texture_id) {
+                texture_config.try_into_linear(&texture_id)?
+            } else {
+                texture_config
+            };
             let texture = texture_config.try_make_texture(&textures)?;
             textures.insert(texture_id.clone(), texture);
         }
@@ -425,51 +1666,142 @@ impl SceneConfig {
                 Arc::new(SolidColor::new(0.5*DVec3::ONE))
             };
 
+        // Lights given a power spread it over all the surfaces using them in
+        // the whole scene, directly or through the materials wrapping them.
+        // Animated objects count with their transforms at the default frame.
+        let mut areas = HashMap::<Option<Box<str>>, f64>::new();
+        for object_config in self.scene.iter() {
+            object_config.add_material_areas(
+                &self.instances,
+                &None,
+                DMat3::IDENTITY,
+                DEFAULT_FRAME as f64,
+                &mut areas,
+            );
+        }
+
+        // Wrapped materials are always declared before the materials
+        // wrapping them.
+        if let Some(area) = areas.get(&None).copied()
+            && let Some(material_fallback) = self.material_fallback.as_ref() {
+            for wrapped_id in material_fallback.get_wrapped_materials() {
+                *areas.entry(Some(Box::from(wrapped_id))).or_default() += area;
+            }
+        }
+
+        for (material_id, material_config) in self.materials.iter().rev() {
+            if let Some(area) = areas.get(&Some(material_id.clone())).copied() {
+                for wrapped_id in material_config.get_wrapped_materials() {
+                    *areas.entry(Some(Box::from(wrapped_id))).or_default() += area;
+                }
+            }
+        }
+
+        // Wrappers are built around the lights already adapted to their
+        // area and are left as is.
         let mut materials = MaterialMap::new();
         for (material_id, material_config) in self.materials {
             let material = material_config.try_make_material(
                 &textures,
                 texture_fallback.clone(),
+                &materials,
             )?;
+            let material = areas
+                .get(&Some(material_id.clone()))
+                .and_then(|area| material.for_surface_area(*area))
+                .unwrap_or(material);
+
             materials.insert(material_id.clone(), material);
         }
 
         let material_fallback = material_fallback.unwrap_or(
             if let Some(material_fallback) = self.material_fallback {
-                material_fallback.try_make_material(&textures, texture_fallback.clone())?
+                material_fallback.try_make_material(&textures, texture_fallback.clone(), &materials)?
             } else {
                 Arc::new(Lambertian::with_texture(texture_fallback.clone()))
             }
         );
+        let material_fallback = areas
+            .get(&None)
+            .and_then(|area| material_fallback.for_surface_area(*area))
+            .unwrap_or(material_fallback);
+
         let mut instances = InstanceMap::new();
         for (instance_id, instance_config) in self.instances {
+            if instance_config.is_animated() {
+                return Err(anyhow!("animated object cannot be used as instance: '{instance_id}'"));
+            }
+
             let object = instance_config.try_make_object(
                 &instances,
                 &materials,
                 material_fallback.clone(),
+                DEFAULT_FRAME as f64,
             )?;
             instances.insert(instance_id.clone(), object);
         }
+        let background = self.background
+            .map(|background| background.try_make_background(&textures))
+            .transpose()?;
+        let lights = light_configs
+            .iter()
+            .map(LightConfig::make_light)
+            .collect();
+
+        let (animated_objects, static_object_configs): (Vec<_>, Vec<_>) =
+            self.scene
+                .into_iter()
+                .partition(ObjectConfig::is_animated);
+
         let mut objects = Vec::new();
-        for object_config in self.scene {
+        for object_config in static_object_configs {
             let object = object_config.try_make_object(
                 &instances,
                 &materials,
                 material_fallback.clone(),
+                DEFAULT_FRAME as f64,
             )?;
             objects.push(object);
         }
 
-        let mut camera_builder = CameraBuilder::default();
+        Ok(SceneAnimation {
+            camera: self.camera,
+            camera_keyframes: self.camera_keyframes,
+            camera_overrides: CameraConfig::default(),
+            background,
+            lights,
+            instances,
+            materials,
+            material_fallback,
+            static_objects: BVH::from(objects.as_mut_slice()),
+            animated_objects,
+        })
+    }
+
+    // Lights of the scene and of the scenes it includes.
+    fn try_get_lights(&self, transformed: bool) -> Result<Vec<LightConfig>> {
+        let instances = self.instances
+            .iter()
+            .map(|(instance_id, instance_config)| (instance_id.as_ref(), instance_config))
+            .collect::<HashMap<_, _>>();
+        let mut referenced = HashSet::new();
+        let mut lights = self.lights.clone();
 
-        self.camera.try_update(&mut camera_builder)?;
+        for object_config in self.scene.iter() {
+            lights.extend(object_config.try_get_included_lights(&instances, transformed, &mut referenced)?);
+        }
 
-        let camera = camera_builder.build();
+        Ok(lights)
+    }
 
-        Ok(Scene {
-            camera,
-            objects: BVH::from(objects.as_mut_slice()),
-        })
+    // Included scenes are loaded, along with the scenes they include, once
+    // for all the frames.
+    fn try_resolve_includes(&mut self) -> Result<()> {
+        for object_config in self.instances.iter_mut().map(|(_, object)| object).chain(self.scene.iter_mut()) {
+            object_config.try_resolve_includes()?;
+        }
+
+        Ok(())
     }
 
     pub fn try_load_scene<P: AsRef<Path>>(path: P) -> Result<Self> {
@@ -491,7 +1823,8 @@ impl SceneConfig {
         Ok(scene_config)
     }
 
-    pub fn try_build(self) -> Result<Scene> {
+    pub fn try_build_animation(mut self) -> Result<SceneAnimation> {
+        self.try_resolve_includes()?;
         self.try_build_aux(Option::None)
     }
 }